@@ -1,11 +1,18 @@
 use macroquad::prelude::clear_background;
 use macroquad::prelude::next_frame;
+use macroquad::prelude::set_camera;
+use macroquad::prelude::set_default_camera;
 use macroquad::prelude::Color;
 use macroquad::prelude::BLACK;
+use std::path::PathBuf;
 
 use super::EditorButtons;
+use super::EditorCamera;
 use super::EditorElements;
 use super::EditorHelps;
+use super::EditorLayer;
+use super::EditorPicker;
+use super::EditorPrompt;
 use super::EditorState;
 
 pub struct Editor {
@@ -16,23 +23,59 @@ pub struct Editor {
 impl Editor {
     pub fn new() -> Self {
         let color = BLACK;
-        let state = EditorState::new();
+        let mut state = EditorState::new();
+        if let Some(artboard) = state.artboards.first() {
+            state.camera.center(artboard.rect().center());
+        }
         Editor { color, state }
     }
 
+    pub fn open(&mut self, path: PathBuf) {
+        self.state.open(path);
+    }
+
     pub async fn run(&mut self) {
         loop {
             clear_background(self.color.with_alpha(0.5));
 
-            EditorButtons::actions(&mut self.state);
-            EditorButtons::draw(&mut self.state);
+            // The frame a prompt is confirmed or cancelled in leaves its
+            // Enter or Escape to the prompt alone.
+            let prompted = self.state.prompt.is_some();
+            EditorPrompt::actions(&mut self.state);
 
-            EditorElements::actions(&mut self.state);
+            // The canvas is drawn through the camera, buttons and overlays
+            // stay in screen space on top.
+            if !prompted {
+                EditorCamera::actions(&mut self.state);
+            }
+            set_camera(&self.state.camera.camera());
+
+            if !prompted {
+                EditorButtons::actions(&mut self.state);
+                EditorElements::actions(&mut self.state);
+            }
             EditorElements::draw(&mut self.state);
 
-            EditorHelps::actions(&mut self.state);
             EditorHelps::draw(&mut self.state);
 
+            set_default_camera();
+
+            EditorButtons::draw(&mut self.state);
+
+            EditorHelps::actions(&mut self.state);
+
+            // After the canvas, which leaves the clicks on the panel to it.
+            if !prompted {
+                EditorPicker::actions(&mut self.state);
+            }
+            EditorPicker::draw(&mut self.state);
+            if !prompted {
+                EditorLayer::actions(&mut self.state);
+            }
+            EditorLayer::draw(&mut self.state);
+
+            EditorPrompt::draw(&mut self.state);
+
             next_frame().await;
         }
     }
@@ -0,0 +1,136 @@
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use serde::Deserialize;
+use serde::Serialize;
+
+// Space left between a new artboard and the ones already on the canvas.
+pub const ARTBOARD_GAP: f32 = 40.0;
+
+// Named area of the canvas such as a screen or a sprite, shown as a frame,
+// snapped to and used to crop exports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorArtboard {
+    pub name: String,
+    pub point: Vec2,
+    pub size: Vec2,
+}
+
+impl EditorArtboard {
+    pub fn new(name: &str, point: Vec2, size: Vec2) -> Self {
+        Self {
+            name: name.to_string(),
+            point,
+            size,
+        }
+    }
+
+    // The display frames documents had before artboards, sharing their
+    // center with the smaller one at the origin.
+    pub fn defaults() -> Vec<Self> {
+        let display = Vec2::new(640.0, 480.0);
+        let display_hd = Vec2::new(1280.0, 720.0);
+        vec![
+            EditorArtboard::new("DISPLAY", Vec2::ZERO, display),
+            EditorArtboard::new("DISPLAY HD", (display - display_hd) / 2.0, display_hd),
+        ]
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.point.x, self.point.y, self.size.x, self.size.y)
+    }
+
+    pub fn text(&self) -> String {
+        format!(
+            "{} {}X{}",
+            self.name,
+            self.size.x.round() as u32,
+            self.size.y.round() as u32
+        )
+    }
+
+    // Reads `NAME WIDTHxHEIGHT` as written by `text`, e.g. `SPRITE 32x32`.
+    pub fn parse(text: &str) -> Option<(String, Vec2)> {
+        let text = text.trim();
+        let (name, size) = text.rsplit_once(' ').unwrap_or(("", text));
+        let size = size.to_lowercase();
+        let (width, height) = size.split_once('x')?;
+        let size = Vec2::new(width.trim().parse().ok()?, height.trim().parse().ok()?);
+        if size.x < 1.0 || size.y < 1.0 {
+            return None;
+        }
+        Some((name.trim().to_uppercase(), size))
+    }
+
+    // Top left corner for a new artboard, right of all the others.
+    pub fn place(artboards: &[Self]) -> Vec2 {
+        artboards
+            .iter()
+            .map(|i| i.rect())
+            .reduce(|a, b| a.combine_with(b))
+            .map(|i| Vec2::new(i.right() + ARTBOARD_GAP, i.y))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+
+    use super::EditorArtboard;
+    use crate::editor::EditorFrames;
+    use crate::editor::EditorState;
+
+    #[test]
+    fn parses_names_and_sizes() {
+        assert_eq!(
+            EditorArtboard::parse("sprite 32x32"),
+            Some(("SPRITE".to_string(), Vec2::new(32.0, 32.0)))
+        );
+        assert_eq!(
+            EditorArtboard::parse("DISPLAY FULL HD 1920X1080"),
+            Some(("DISPLAY FULL HD".to_string(), Vec2::new(1920.0, 1080.0)))
+        );
+        assert_eq!(
+            EditorArtboard::parse("64x48"),
+            Some((String::new(), Vec2::new(64.0, 48.0)))
+        );
+        assert_eq!(EditorArtboard::parse("SPRITE"), None);
+        assert_eq!(EditorArtboard::parse("SPRITE 0x32"), None);
+
+        let artboard = EditorArtboard::new("ICON", Vec2::ZERO, Vec2::new(16.0, 24.0));
+        assert_eq!(
+            EditorArtboard::parse(&artboard.text()),
+            Some(("ICON".to_string(), artboard.size))
+        );
+
+        let defaults = EditorArtboard::defaults();
+        assert_eq!(defaults[1].rect().center(), defaults[0].rect().center());
+        assert_eq!(
+            EditorArtboard::place(&defaults),
+            Vec2::new(960.0 + super::ARTBOARD_GAP, -120.0)
+        );
+    }
+
+    #[test]
+    fn artboard_changes_undo() {
+        let mut state = EditorState::new();
+        let artboards = state.artboards.clone();
+        state.artboard = 1;
+        state.export.frame = EditorFrames::Artboard(1);
+
+        state.artboard_edit("sprite 32x32");
+        assert_eq!(state.artboards[1].name, "SPRITE");
+        state.artboard_remove();
+        assert_eq!(state.artboards.len(), artboards.len() - 1);
+        assert_eq!(state.export.frame, EditorFrames::Content);
+
+        state.undo();
+        assert_eq!(state.artboards[1].name, "SPRITE");
+        assert_eq!(state.export.frame, EditorFrames::Artboard(1));
+        state.undo();
+        assert_eq!(state.artboards, artboards);
+        state.redo();
+        state.redo();
+        assert_eq!(state.artboards.len(), artboards.len() - 1);
+    }
+}
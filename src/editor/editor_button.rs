@@ -36,6 +36,8 @@ impl EditorButton {
         let height = screen_height();
 
         let left_top_buttons: Vec<EditorButton> = [
+            EditorButtons::Save,
+            EditorButtons::Open,
             EditorButtons::Grid,
             EditorButtons::Snap,
             EditorButtons::Redo,
@@ -51,47 +53,69 @@ impl EditorButton {
         })
         .collect();
 
+        let right_bottom_buttons: Vec<EditorButton> = [
+            EditorButtons::Thinner,
+            EditorButtons::Thickness,
+            EditorButtons::Thicker,
+            EditorButtons::Fill,
+            EditorButtons::Stroke,
+            EditorButtons::Color,
+        ]
+        .iter()
+        .rev()
+        .scan((width, height - 10.0), |(x, y), &button| {
+            let dimensions = button.dimensions();
+            *x -= dimensions.width + 10.0;
+            Some(EditorButton::new(button, *x, *y, BUTTON_SIZE))
+        })
+        .collect();
+        let right_bottom_x = right_bottom_buttons.iter().fold(width, |x, i| x.min(i.x));
+
         let left_bottom_buttons: Vec<EditorButton> = [
             EditorButtons::Line,
-            // EditorButtons::Arc,
-            // EditorButtons::Poly,
+            EditorButtons::Arc,
+            EditorButtons::Pie,
+            EditorButtons::Poly,
             EditorButtons::Circle,
-            EditorButtons::CircleLine,
             EditorButtons::Ellipse,
-            EditorButtons::EllipseLine,
             EditorButtons::Rectangle,
             EditorButtons::Triangle,
             EditorButtons::Hexagon,
+            EditorButtons::Polyline,
+            EditorButtons::Polygon,
+            EditorButtons::Path,
+            EditorButtons::Select,
         ]
         .iter()
         .rev()
         .scan((10.0, height - (BUTTON_SIZE / 2.0)), |(x, y), &button| {
             let dimensions = button.dimensions();
+            // Tools wrap onto a row above before reaching the style buttons.
+            if *x + dimensions.width > right_bottom_x - 10.0 {
+                *x = 10.0;
+                *y -= BUTTON_SIZE + 5.0;
+            }
             let text_x = *x;
             *x += dimensions.width + 10.0;
             Some(EditorButton::new(button, text_x, *y, BUTTON_SIZE))
         })
         .collect();
 
-        let right_top_buttons: Vec<EditorButton> = [EditorButtons::Help]
-            .iter()
-            .rev()
-            .scan((width, 20.0), |(x, y), &button| {
-                let dimensions = button.dimensions();
-                *x -= dimensions.width + 10.0;
-                Some(EditorButton::new(button, *x, *y, BUTTON_SIZE))
-            })
-            .collect();
-
-        let right_bottom_buttons: Vec<EditorButton> = [EditorButtons::Color]
-            .iter()
-            .rev()
-            .scan((width, height - 10.0), |(x, y), &button| {
-                let dimensions = button.dimensions();
-                *x -= dimensions.width + 10.0;
-                Some(EditorButton::new(button, *x, *y, BUTTON_SIZE))
-            })
-            .collect();
+        let right_top_buttons: Vec<EditorButton> = [
+            EditorButtons::Layers,
+            EditorButtons::ZoomOut,
+            EditorButtons::ZoomIn,
+            EditorButtons::Zoom,
+            EditorButtons::Help,
+        ]
+        .iter()
+        .rev()
+        .scan((width, 20.0), |(x, y), &button| {
+            let dimensions = button.dimensions();
+            *x -= dimensions.width + 10.0;
+            Some(EditorButton::new(button, *x, *y, BUTTON_SIZE))
+        })
+        .collect();
 
         [
             left_top_buttons,
@@ -5,7 +5,6 @@ use macroquad::prelude::is_key_released;
 use macroquad::prelude::is_mouse_button_pressed;
 use macroquad::prelude::measure_text;
 use macroquad::prelude::mouse_position;
-use macroquad::prelude::Color;
 use macroquad::prelude::KeyCode;
 use macroquad::prelude::MouseButton;
 use macroquad::prelude::TextDimensions;
@@ -14,27 +13,19 @@ use macroquad::prelude::DARKGRAY;
 use macroquad::prelude::GRAY;
 use macroquad::prelude::GREEN;
 use macroquad::prelude::LIGHTGRAY;
-use macroquad::prelude::ORANGE;
-use macroquad::prelude::PINK;
-use macroquad::prelude::PURPLE;
-use macroquad::prelude::RED;
-use macroquad::prelude::YELLOW;
 
 use super::EditorButton;
 use super::EditorElements;
+use super::EditorOrders;
+use super::EditorPicker;
+use super::EditorPickers;
+use super::EditorPrompt;
+use super::EditorPrompts;
 use super::EditorState;
-
-pub const COLORS: [Color; 9] = [
-    DARKGRAY.with_alpha(0.5),
-    GRAY.with_alpha(0.5),
-    GREEN.with_alpha(0.5),
-    PINK.with_alpha(0.5),
-    LIGHTGRAY.with_alpha(0.5),
-    ORANGE.with_alpha(0.5),
-    PURPLE.with_alpha(0.5),
-    RED.with_alpha(0.5),
-    YELLOW.with_alpha(0.5),
-];
+use super::EditorSvg;
+use super::CAMERA_ZOOM_STEP;
+use super::PALETTE_EXTENSION;
+use super::THICKNESS_STEP;
 
 pub const BUTTON_SIZE: f32 = 21.0;
 
@@ -45,23 +36,32 @@ pub enum EditorButtons {
     Help,
     Grid,
     Snap,
+    Open,
+    Save,
+    Fill,
+    Stroke,
     Color,
-    // Thickness,
-    // Zoom,
-    // ZoomIn,
-    // ZoonOut,
+    Thinner,
+    Thickness,
+    Thicker,
+    Zoom,
+    ZoomIn,
+    ZoomOut,
+    Layers,
+    Select,
     Line,
-    // Arc,
-    // Poly,
-    // PolyLine,
+    Arc,
+    Pie,
+    Poly,
     Circle,
-    CircleLine,
     Ellipse,
-    EllipseLine,
     Rectangle,
     // RectangleLine,
     Triangle,
     Hexagon,
+    Polyline,
+    Polygon,
+    Path,
 }
 
 impl EditorButtons {
@@ -72,19 +72,31 @@ impl EditorButtons {
             EditorButtons::Help => "HELP",
             EditorButtons::Grid => "GRID",
             EditorButtons::Snap => "SNAP",
+            EditorButtons::Open => "OPEN",
+            EditorButtons::Save => "SAVE",
+            EditorButtons::Fill => "FILL",
+            EditorButtons::Stroke => "STROKE",
             EditorButtons::Color => "COLOR",
-            // EditorButtons::ZoomIn => "ZOOM_IN",
-            // EditorButtons::ZoomOut => "ZOOM_OUT",
+            EditorButtons::Thinner => "-",
+            EditorButtons::Thickness => "WIDTH",
+            EditorButtons::Thicker => "+",
+            EditorButtons::Zoom => "FIT",
+            EditorButtons::ZoomIn => "ZOOM IN",
+            EditorButtons::ZoomOut => "ZOOM OUT",
+            EditorButtons::Layers => "LAYERS",
+            EditorButtons::Select => "SELECT",
             EditorButtons::Line => "LINE",
-            // EditorButtons::Arc => "ARC",
-            // EditorButtons::Poly => "POLY",
+            EditorButtons::Arc => "ARC",
+            EditorButtons::Pie => "PIE",
+            EditorButtons::Poly => "POLY",
             EditorButtons::Circle => "CIRCLE",
-            EditorButtons::CircleLine => "CIRCLE LINE",
             EditorButtons::Ellipse => "ELLIPSE",
-            EditorButtons::EllipseLine => "ELLIPSE LINE",
             EditorButtons::Rectangle => "RECTANGLE",
             EditorButtons::Triangle => "TRIANGLE",
             EditorButtons::Hexagon => "HEXAGON",
+            EditorButtons::Polyline => "POLYLINE",
+            EditorButtons::Polygon => "POLYGON",
+            EditorButtons::Path => "PATH",
         }
     }
     pub fn dimensions(&self) -> TextDimensions {
@@ -121,6 +133,26 @@ impl EditorButtons {
                         GRAY
                     }
                 }
+                EditorButtons::Open
+                | EditorButtons::Save
+                | EditorButtons::Thinner
+                | EditorButtons::Thicker
+                | EditorButtons::Zoom
+                | EditorButtons::ZoomIn
+                | EditorButtons::ZoomOut => {
+                    if is_position {
+                        LIGHTGRAY
+                    } else {
+                        GRAY
+                    }
+                }
+                EditorButtons::Layers => {
+                    if is_position || state.layer_panel {
+                        GREEN
+                    } else {
+                        GRAY
+                    }
+                }
                 EditorButtons::Help => {
                     if is_position || state.help {
                         GREEN
@@ -142,22 +174,49 @@ impl EditorButtons {
                         GRAY
                     }
                 }
+                EditorButtons::Select => {
+                    if is_position || !state.draw {
+                        GREEN
+                    } else {
+                        GRAY
+                    }
+                }
                 EditorButtons::Circle
-                | EditorButtons::CircleLine
                 | EditorButtons::Ellipse
-                | EditorButtons::EllipseLine
                 | EditorButtons::Line
-                // | EditorButtons::Arc
-                // | EditorButtons::Poly
+                | EditorButtons::Arc
+                | EditorButtons::Pie
+                | EditorButtons::Poly
                 | EditorButtons::Rectangle
                 | EditorButtons::Triangle
-                | EditorButtons::Hexagon => {
+                | EditorButtons::Hexagon
+                | EditorButtons::Polyline
+                | EditorButtons::Polygon
+                | EditorButtons::Path => {
                     if is_position || i.button == EditorButtons::from(state.element) && state.draw {
                         GREEN
                     } else {
                         GRAY
                     }
                 }
+                EditorButtons::Fill => {
+                    if is_position {
+                        LIGHTGRAY
+                    } else if state.element_fill {
+                        state.element_color
+                    } else {
+                        DARKGRAY
+                    }
+                }
+                EditorButtons::Stroke => {
+                    if is_position {
+                        LIGHTGRAY
+                    } else if state.element_stroke {
+                        state.element_stroke_color
+                    } else {
+                        DARKGRAY
+                    }
+                }
                 EditorButtons::Color => {
                     if is_position {
                         LIGHTGRAY
@@ -165,11 +224,25 @@ impl EditorButtons {
                         state.element_color
                     }
                 }
+                EditorButtons::Thickness => {
+                    // The width of new strokes sits above the label.
+                    let value = format!("{}", state.element_thickness);
+                    draw_text(&value, i.x, i.y - i.size, i.size * 0.75, GRAY);
+                    if is_position {
+                        LIGHTGRAY
+                    } else {
+                        GRAY
+                    }
+                }
             };
             draw_text(text, i.x, i.y, i.size, color);
         }
     }
     pub fn actions(state: &mut EditorState) {
+        if state.prompt.is_some() {
+            return;
+        }
+
         if is_key_pressed(KeyCode::Z) && is_key_down(KeyCode::LeftSuper) {
             state.undo();
         }
@@ -178,8 +251,21 @@ impl EditorButtons {
         }
 
         if is_key_pressed(KeyCode::S) && is_key_down(KeyCode::LeftSuper) {
+            if is_key_down(KeyCode::LeftShift) {
+                state.write_as();
+            } else {
+                state.write_current();
+            }
+        }
+        if is_key_pressed(KeyCode::Semicolon) && is_key_down(KeyCode::LeftSuper) {
             state.snap = !state.snap;
         }
+        if is_key_pressed(KeyCode::O) && is_key_down(KeyCode::LeftSuper) {
+            state.prompt = Some(EditorPrompt::new(EditorPrompts::Open, String::new()));
+        }
+        if is_key_pressed(KeyCode::I) && is_key_down(KeyCode::LeftSuper) {
+            state.prompt = Some(EditorPrompt::new(EditorPrompts::Import, String::new()));
+        }
         if is_key_pressed(KeyCode::G) && is_key_down(KeyCode::LeftSuper) {
             if state.grid > 2 {
                 state.grid = 0;
@@ -191,6 +277,58 @@ impl EditorButtons {
         if is_key_pressed(KeyCode::Key1) && is_key_down(KeyCode::LeftSuper) {
             state.element = EditorElements::Line;
         }
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if is_key_pressed(KeyCode::Key1) && shift {
+            state.zoom_fit();
+        }
+        if is_key_pressed(KeyCode::Key2) && shift {
+            state.zoom_selection();
+        }
+        if is_key_pressed(KeyCode::Key0) && shift {
+            let zoom = state.camera.zoom;
+            state.zoom(1.0 / zoom);
+        }
+        if is_key_pressed(KeyCode::Equal) && is_key_down(KeyCode::LeftSuper) {
+            state.zoom(CAMERA_ZOOM_STEP);
+        }
+        if is_key_pressed(KeyCode::Minus) && is_key_down(KeyCode::LeftSuper) {
+            state.zoom(1.0 / CAMERA_ZOOM_STEP);
+        }
+        if is_key_pressed(KeyCode::A) && !is_key_down(KeyCode::LeftSuper) {
+            state.button = Some(EditorButtons::Select);
+            state.draw = false;
+            state.current = None;
+            state.direct = false;
+        }
+        if is_key_pressed(KeyCode::D) && is_key_down(KeyCode::LeftSuper) {
+            state.duplicate();
+        }
+        if is_key_pressed(KeyCode::C) && is_key_down(KeyCode::LeftSuper) {
+            state.copy();
+        }
+        if is_key_pressed(KeyCode::X) && is_key_down(KeyCode::LeftSuper) {
+            state.cut();
+        }
+        if is_key_pressed(KeyCode::V) && is_key_down(KeyCode::LeftSuper) {
+            if is_key_down(KeyCode::LeftShift) {
+                let position = state.position();
+                state.paste(Some(position));
+            } else {
+                state.paste(None);
+            }
+        }
+        if is_key_pressed(KeyCode::D) && !is_key_down(KeyCode::LeftSuper) {
+            state.button = Some(EditorButtons::Select);
+            state.draw = false;
+            state.current = None;
+            state.direct = !state.direct;
+        }
+        if is_key_pressed(KeyCode::M) {
+            state.marquee_mode = state.marquee_mode.next();
+        }
+        if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+            state.delete();
+        }
 
         if is_key_down(KeyCode::H) {
             state.help = true;
@@ -199,28 +337,113 @@ impl EditorButtons {
             state.help = false;
         }
         if is_key_pressed(KeyCode::E) {
-            state.export();
+            if is_key_down(KeyCode::LeftSuper) {
+                let text = state
+                    .export
+                    .target
+                    .as_ref()
+                    .map(|i| i.display().to_string())
+                    .unwrap_or_default();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportTarget, text));
+            } else if is_key_down(KeyCode::LeftAlt) {
+                let text = state.export.module.clone();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportModule, text));
+            } else if is_key_down(KeyCode::LeftShift) {
+                let text = state.export.name.clone();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportName, text));
+            } else {
+                state.export();
+            }
+        }
+        if is_key_pressed(KeyCode::V) && !is_key_down(KeyCode::LeftSuper) {
+            state.export_svg();
+        }
+        if is_key_pressed(KeyCode::P) {
+            if is_key_down(KeyCode::LeftAlt) {
+                let text = state
+                    .export
+                    .background
+                    .map(EditorSvg::color)
+                    .unwrap_or("none".to_string());
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportBackground, text));
+            } else if is_key_down(KeyCode::LeftShift) {
+                let text = state.export.scale.to_string();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportScale, text));
+            } else {
+                state.export_png();
+            }
+        }
+        if is_key_pressed(KeyCode::F) {
+            state.export.frame = state.export.frame.next(&state.artboards);
+        }
+        if is_key_pressed(KeyCode::B) {
+            if is_key_down(KeyCode::LeftSuper) {
+                state.artboard_remove();
+            } else if is_key_down(KeyCode::LeftAlt) {
+                let text = "SPRITE 32x32".to_string();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ArtboardAdd, text));
+            } else if is_key_down(KeyCode::LeftShift) {
+                if let Some(artboard) = state.artboards.get(state.artboard) {
+                    let text = artboard.text();
+                    state.prompt = Some(EditorPrompt::new(EditorPrompts::ArtboardEdit, text));
+                }
+            } else {
+                state.artboard_next();
+            }
+        }
+        // CMD moves the selection in the draw order instead, SHIFT all the way.
+        if is_key_pressed(KeyCode::LeftBracket) {
+            if is_key_down(KeyCode::LeftSuper) && is_key_down(KeyCode::LeftShift) {
+                state.order(EditorOrders::Back);
+            } else if is_key_down(KeyCode::LeftSuper) {
+                state.order(EditorOrders::Backward);
+            } else {
+                state.thickness(state.element_thickness - THICKNESS_STEP);
+            }
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            if is_key_down(KeyCode::LeftSuper) && is_key_down(KeyCode::LeftShift) {
+                state.order(EditorOrders::Front);
+            } else if is_key_down(KeyCode::LeftSuper) {
+                state.order(EditorOrders::Forward);
+            } else {
+                state.thickness(state.element_thickness + THICKNESS_STEP);
+            }
+        }
+        if is_key_pressed(KeyCode::L) {
+            state.layer_panel = !state.layer_panel;
+        }
+        if is_key_pressed(KeyCode::K) {
+            if is_key_down(KeyCode::LeftSuper) {
+                let text = state.export.palette.clone();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::ExportPalette, text));
+            } else if is_key_down(KeyCode::LeftAlt) {
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::PaletteAdd, String::new()));
+            } else if is_key_down(KeyCode::LeftShift) {
+                let text = state
+                    .palette_path
+                    .as_ref()
+                    .map(|i| i.display().to_string())
+                    .unwrap_or(format!("palette.{}", PALETTE_EXTENSION));
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::PaletteSave, text));
+            } else {
+                let text = state
+                    .palette_path
+                    .as_ref()
+                    .map(|i| i.display().to_string())
+                    .unwrap_or_default();
+                state.prompt = Some(EditorPrompt::new(EditorPrompts::PaletteOpen, text));
+            }
         }
 
         if is_mouse_button_pressed(MouseButton::Left) {
-            if let Some(button) = EditorButton::find().take() {
-                if [
-                    EditorButtons::Ellipse,
-                    EditorButtons::Line,
-                    EditorButtons::Triangle,
-                    EditorButtons::Rectangle,
-                    EditorButtons::Circle,
-                    EditorButtons::Hexagon,
-                ]
-                .contains(&button.button)
-                {
-                    if state.button == Some(button.button) {
-                        state.draw = !state.draw;
-                    } else {
-                        state.draw = true;
-                    }
-                }
+            if let Some(button) = EditorButton::find() {
                 match button.button {
+                    EditorButtons::Select => {
+                        state.button = Some(EditorButtons::Select);
+                        state.draw = false;
+                        state.current = None;
+                    }
                     EditorButtons::Undo => {
                         state.button = Some(EditorButtons::Undo);
                         state.undo();
@@ -233,45 +456,86 @@ impl EditorButtons {
                         state.button = Some(EditorButtons::Help);
                         state.help = !state.help;
                     }
-                    // EditorButtons::Arc => {
-                    //     // state.button = Some(EditorButtons::Arc);
-                    //     // state.element = EditorElements::Arc;
-                    // }
-                    // EditorButtons::Poly => {
-                    //     // state.button = Some(EditorButtons::Poly);
-                    //     // state.element = EditorElements::Poly;
-                    // }
+                    EditorButtons::Zoom => {
+                        state.zoom_fit();
+                    }
+                    EditorButtons::ZoomIn => {
+                        state.zoom(CAMERA_ZOOM_STEP);
+                    }
+                    EditorButtons::ZoomOut => {
+                        state.zoom(1.0 / CAMERA_ZOOM_STEP);
+                    }
+                    EditorButtons::Arc => {
+                        state.button = Some(EditorButtons::Arc);
+                        state.element = EditorElements::Arc;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
+                    EditorButtons::Pie => {
+                        state.button = Some(EditorButtons::Pie);
+                        state.element = EditorElements::Pie;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
+                    EditorButtons::Poly => {
+                        state.button = Some(EditorButtons::Poly);
+                        state.element = EditorElements::Poly;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
                     EditorButtons::Ellipse => {
                         state.button = Some(EditorButtons::Ellipse);
                         state.element = EditorElements::Ellipse;
-                    }
-                    EditorButtons::EllipseLine => {
-                        state.button = Some(EditorButtons::EllipseLine);
-                        state.element = EditorElements::EllipseLine;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Rectangle => {
                         state.button = Some(EditorButtons::Rectangle);
                         state.element = EditorElements::Rectangle;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Triangle => {
                         state.button = Some(EditorButtons::Triangle);
                         state.element = EditorElements::Triangle;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Hexagon => {
                         state.button = Some(EditorButtons::Hexagon);
                         state.element = EditorElements::Hexagon;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
+                    EditorButtons::Polyline => {
+                        state.button = Some(EditorButtons::Polyline);
+                        state.element = EditorElements::Polyline;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
+                    EditorButtons::Polygon => {
+                        state.button = Some(EditorButtons::Polygon);
+                        state.element = EditorElements::Polygon;
+                        state.draw = true;
+                        state.selection.clear();
+                    }
+                    EditorButtons::Path => {
+                        state.button = Some(EditorButtons::Path);
+                        state.element = EditorElements::Path;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Line => {
                         state.button = Some(EditorButtons::Line);
                         state.element = EditorElements::Line;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Circle => {
                         state.button = Some(EditorButtons::Circle);
                         state.element = EditorElements::Circle;
-                    }
-                    EditorButtons::CircleLine => {
-                        state.button = Some(EditorButtons::CircleLine);
-                        state.element = EditorElements::CircleLine;
+                        state.draw = true;
+                        state.selection.clear();
                     }
                     EditorButtons::Grid => {
                         state.button = Some(EditorButtons::Grid);
@@ -285,13 +549,51 @@ impl EditorButtons {
                         state.button = Some(EditorButtons::Snap);
                         state.snap = !state.snap;
                     }
+                    EditorButtons::Open => {
+                        state.button = Some(EditorButtons::Open);
+                        state.prompt = Some(EditorPrompt::new(EditorPrompts::Open, String::new()));
+                    }
+                    EditorButtons::Save => {
+                        state.button = Some(EditorButtons::Save);
+                        state.write_current();
+                    }
+                    EditorButtons::Fill => {
+                        state.element_fill = !state.element_fill;
+                        // Shapes keep at least a fill or a stroke.
+                        state.element_stroke |= !state.element_fill;
+                        state.restyle();
+                    }
+                    EditorButtons::Stroke => {
+                        state.element_stroke = !state.element_stroke;
+                        state.element_fill |= !state.element_stroke;
+                        state.restyle();
+                    }
                     EditorButtons::Color => {
-                        if state.element_color_index >= COLORS.len() - 1 {
-                            state.element_color_index = 0;
+                        let shift =
+                            is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                        // SHIFT opens the picker on the stroke color.
+                        let target = if shift {
+                            EditorPickers::Stroke
                         } else {
-                            state.element_color_index += 1;
-                        }
-                        state.element_color = COLORS[state.element_color_index];
+                            EditorPickers::Fill
+                        };
+                        state.picker = match state.picker {
+                            Some(_) if !shift => None,
+                            _ => Some(EditorPicker::new(target, state.color(target))),
+                        };
+                    }
+                    EditorButtons::Layers => {
+                        state.layer_panel = !state.layer_panel;
+                    }
+                    EditorButtons::Thinner => {
+                        state.thickness(state.element_thickness - THICKNESS_STEP);
+                    }
+                    EditorButtons::Thickness => {
+                        let text = state.element_thickness.to_string();
+                        state.prompt = Some(EditorPrompt::new(EditorPrompts::Thickness, text));
+                    }
+                    EditorButtons::Thicker => {
+                        state.thickness(state.element_thickness + THICKNESS_STEP);
                     }
                 }
             }
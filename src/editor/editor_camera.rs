@@ -0,0 +1,171 @@
+use macroquad::prelude::camera_font_scale;
+use macroquad::prelude::draw_text_ex;
+use macroquad::prelude::is_key_down;
+use macroquad::prelude::is_mouse_button_pressed;
+use macroquad::prelude::is_mouse_button_released;
+use macroquad::prelude::mouse_position;
+use macroquad::prelude::mouse_wheel;
+use macroquad::prelude::screen_height;
+use macroquad::prelude::screen_width;
+use macroquad::prelude::Camera2D;
+use macroquad::prelude::Color;
+use macroquad::prelude::KeyCode;
+use macroquad::prelude::MouseButton;
+use macroquad::prelude::Rect;
+use macroquad::prelude::TextParams;
+use macroquad::prelude::Vec2;
+
+use super::EditorButton;
+use super::EditorElements;
+use super::EditorState;
+
+pub const CAMERA_ZOOM_MIN: f32 = 0.1;
+pub const CAMERA_ZOOM_MAX: f32 = 64.0;
+// Zoom factor of one wheel step or zoom command.
+pub const CAMERA_ZOOM_STEP: f32 = 1.2;
+// Screen margin kept around the shapes when zooming to fit.
+pub const CAMERA_FIT_MARGIN: f32 = 40.0;
+
+// Maps the canvas to the window, `offset` is where the canvas origin lands on
+// screen and `zoom` the screen pixels per canvas unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EditorCamera {
+    pub offset: Vec2,
+    pub zoom: f32,
+    // Screen position of the pointer while panning.
+    pub pan: Option<Vec2>,
+}
+
+impl EditorCamera {
+    pub fn new() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            zoom: 1.0,
+            pan: None,
+        }
+    }
+
+    pub fn world(&self, screen: Vec2) -> Vec2 {
+        (screen - self.offset) / self.zoom
+    }
+
+    // Size of a screen pixel in canvas units, keeps overlays and pick
+    // distances the same on screen at any zoom.
+    pub fn unit(&self) -> f32 {
+        1.0 / self.zoom
+    }
+
+    // Canvas area visible in the window.
+    pub fn view(&self) -> Rect {
+        let point = self.world(Vec2::ZERO);
+        let size = Vec2::new(screen_width(), screen_height()) / self.zoom;
+        Rect::new(point.x, point.y, size.x, size.y)
+    }
+
+    pub fn camera(&self) -> Camera2D {
+        let size = Vec2::new(screen_width(), screen_height());
+        Camera2D {
+            target: self.world(size / 2.0),
+            // A positive y zoom keeps the canvas y axis pointing down.
+            zoom: Vec2::splat(2.0 * self.zoom) / size,
+            ..Default::default()
+        }
+    }
+
+    // Zooms by `factor` keeping the canvas point under `screen` in place.
+    pub fn zoom_at(&mut self, screen: Vec2, factor: f32) {
+        let world = self.world(screen);
+        self.zoom = (self.zoom * factor).clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX);
+        self.offset = screen - world * self.zoom;
+    }
+
+    // Moves the canvas point `point` to the middle of the window.
+    pub fn center(&mut self, point: Vec2) {
+        let size = Vec2::new(screen_width(), screen_height());
+        self.offset = size / 2.0 - point * self.zoom;
+    }
+
+    // Zooms so `rect` fills the window inside the margin, empty areas are
+    // only centered.
+    pub fn fit(&mut self, rect: Rect) {
+        let size = Vec2::new(screen_width(), screen_height());
+        let space = (size - CAMERA_FIT_MARGIN * 2.0).max(Vec2::ONE);
+        if rect.w > f32::EPSILON && rect.h > f32::EPSILON {
+            let zoom = (space.x / rect.w).min(space.y / rect.h);
+            self.zoom = zoom.clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX);
+        }
+        self.center(rect.center());
+    }
+
+    // Text of `size` screen pixels at canvas coordinates.
+    pub fn text(&self, text: &str, x: f32, y: f32, size: f32, color: Color) {
+        let (font_size, font_scale, font_scale_aspect) = camera_font_scale(size * self.unit());
+        let params = TextParams {
+            font_size,
+            font_scale,
+            font_scale_aspect,
+            color,
+            ..Default::default()
+        };
+        draw_text_ex(text, x, y, params);
+    }
+
+    pub fn actions(state: &mut EditorState) {
+        if state.prompt.is_some() {
+            return;
+        }
+        let position: Vec2 = mouse_position().into();
+
+        if is_mouse_button_pressed(MouseButton::Middle)
+            || is_mouse_button_pressed(MouseButton::Left) && is_key_down(KeyCode::Space)
+        {
+            state.camera.pan = Some(position);
+        }
+        if let Some(last) = state.camera.pan {
+            state.camera.offset += position - last;
+            state.camera.pan = Some(position);
+        }
+        if is_mouse_button_released(MouseButton::Middle)
+            || is_mouse_button_released(MouseButton::Left)
+        {
+            state.camera.pan = None;
+        }
+
+        // Polygon tools take the wheel for their sides while a shape is dragged.
+        let poly = state.element == EditorElements::Poly;
+        if state.draw && poly && state.current.is_some() {
+            return;
+        }
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0.0 && EditorButton::find().is_none() {
+            let factor = CAMERA_ZOOM_STEP.powf(wheel.signum());
+            state.camera.zoom_at(position, factor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+
+    use super::EditorCamera;
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let mut camera = EditorCamera::new();
+        let cursor = Vec2::new(120.0, 80.0);
+        let world = camera.world(cursor);
+        camera.zoom_at(cursor, 4.0);
+        assert_eq!(camera.zoom, 4.0);
+        assert!(camera.world(cursor).distance(world) < 0.001);
+        assert!(
+            camera
+                .world(Vec2::new(160.0, 80.0))
+                .distance(Vec2::new(130.0, 80.0))
+                < 0.001
+        );
+
+        camera.zoom_at(cursor, 1000.0);
+        assert_eq!(camera.zoom, super::CAMERA_ZOOM_MAX);
+    }
+}
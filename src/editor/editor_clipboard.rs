@@ -0,0 +1,88 @@
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use ron::ser::PrettyConfig;
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+
+use super::EditorElement;
+
+// Offset applied to duplicated elements.
+pub const DUPLICATE_OFFSET: Vec2 = Vec2::new(10.0, 10.0);
+
+// Copied elements, shared with other windows through the system clipboard
+// as RON text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditorClipboard {
+    pub stack: Vec<EditorElement>,
+}
+
+impl EditorClipboard {
+    pub fn new(stack: Vec<EditorElement>) -> Self {
+        Self { stack }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(ron::from_str(content).map_err(|e| format!("Failed to parse clipboard: {}", e))?)
+    }
+
+    pub fn text(&self) -> Result<String, Box<dyn Error>> {
+        let config = PrettyConfig::default().struct_names(true);
+        Ok(ron::ser::to_string_pretty(self, config)
+            .map_err(|e| format!("Failed to serialize clipboard: {}", e))?)
+    }
+
+    pub fn bounds(&self) -> Option<Rect> {
+        self.stack
+            .iter()
+            .map(|i| i.bounds())
+            .reduce(|a, b| a.combine_with(b))
+    }
+
+    // Elements moved so their bounds are centered on `position`.
+    pub fn at(&self, position: Vec2) -> Vec<EditorElement> {
+        let offset = self
+            .bounds()
+            .map(|i| position - i.center())
+            .unwrap_or_default();
+        self.stack
+            .iter()
+            .map(|i| {
+                let mut element = i.clone();
+                element.value.translate(offset);
+                element
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+    use macroquad::prelude::RED;
+
+    use super::EditorClipboard;
+    use crate::editor::EditorElement;
+    use crate::editor::EditorValues;
+
+    #[test]
+    fn round_trips_through_text() {
+        let value = EditorValues::Circle {
+            center: Vec2::new(10.0, 20.0),
+            radius: 5.0,
+        };
+        let clipboard = EditorClipboard::new(vec![EditorElement::new(value, Some(RED), None, 1.0)]);
+        let text = clipboard.text().unwrap();
+        assert_eq!(EditorClipboard::parse(&text).unwrap(), clipboard);
+        assert!(EditorClipboard::parse("plain text").is_err());
+
+        let moved = clipboard.at(Vec2::new(0.0, 0.0));
+        assert_eq!(
+            moved[0].value,
+            EditorValues::Circle {
+                center: Vec2::new(0.0, 0.0),
+                radius: 5.0,
+            }
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use macroquad::prelude::Color;
+use macroquad::prelude::Vec2;
+use ron::ser::PrettyConfig;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::error::Error;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use super::EditorArtboard;
+use super::EditorElement;
+use super::EditorElements;
+use super::EditorExport;
+use super::EditorLayer;
+use super::EditorPalette;
+use super::EditorSegments;
+use super::EditorState;
+use super::EditorValues;
+
+pub const DOCUMENT_VERSION: u32 = 3;
+pub const DOCUMENT_EXTENSION: &str = "ron";
+// Version 1 stored window coordinates with the display frames centered in a
+// window of the default size.
+const DOCUMENT_WINDOW_V1: Vec2 = Vec2::new(800.0, 600.0);
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Color")]
+pub struct EditorColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+// Serializes `Option<Color>` fields through `EditorColor`.
+pub struct EditorColorOption {}
+
+#[derive(Serialize, Deserialize)]
+struct EditorColorValue(#[serde(with = "EditorColor")] Color);
+
+impl EditorColorOption {
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.map(EditorColorValue).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Ok(Option::<EditorColorValue>::deserialize(deserializer)?.map(|i| i.0))
+    }
+}
+
+// Serializes `Vec<Color>` fields through `EditorColor`.
+pub struct EditorColorList {}
+
+impl EditorColorList {
+    pub fn serialize<S: Serializer>(colors: &[Color], serializer: S) -> Result<S::Ok, S::Error> {
+        let colors: Vec<EditorColorValue> = colors.iter().copied().map(EditorColorValue).collect();
+        colors.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Color>, D::Error> {
+        let colors = Vec::<EditorColorValue>::deserialize(deserializer)?;
+        Ok(colors.into_iter().map(|i| i.0).collect())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditorDocument {
+    pub version: u32,
+    pub element: EditorElements,
+    pub snap: bool,
+    pub grid: u16,
+    pub stack: Vec<EditorElement>,
+    #[serde(default)]
+    pub export: EditorExport,
+    // Documents from before artboards had the display frames.
+    #[serde(default = "EditorArtboard::defaults")]
+    pub artboards: Vec<EditorArtboard>,
+    // Recent colors of the picker.
+    #[serde(default, with = "EditorColorList")]
+    pub recent: Vec<Color>,
+    // Named colors elements refer to, kept so documents open without the
+    // palette file.
+    #[serde(default)]
+    pub palette: EditorPalette,
+    // Documents from before layers have all elements in one.
+    #[serde(default = "EditorLayer::defaults")]
+    pub layers: Vec<EditorLayer>,
+}
+
+// Only the version is read first, so older or newer files can be told apart
+// before the rest of the document is parsed.
+#[derive(Deserialize)]
+#[serde(rename = "EditorDocument")]
+struct EditorDocumentVersion {
+    version: u32,
+}
+
+// Elements of versions 1 and 2 had a single color, outlines were separate
+// variants drawn outwards from their radius.
+#[derive(Deserialize)]
+#[serde(rename = "EditorValues")]
+enum EditorValuesV2 {
+    Line {
+        point_a: Vec2,
+        point_b: Vec2,
+        thickness: f32,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    CircleLine {
+        center: Vec2,
+        radius: f32,
+    },
+    Arc {
+        center: Vec2,
+        radius: f32,
+        thickness: f32,
+        start: f32,
+        sweep: f32,
+    },
+    Pie {
+        center: Vec2,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+    Ellipse {
+        center: Vec2,
+        width: f32,
+        height: f32,
+        rotation: f32,
+    },
+    EllipseLine {
+        center: Vec2,
+        width: f32,
+        height: f32,
+        rotation: f32,
+    },
+    Rectangle {
+        point: Vec2,
+        width: f32,
+        height: f32,
+        rotation: f32,
+    },
+    Triangle {
+        point_a: Vec2,
+        point_b: Vec2,
+        point_c: Vec2,
+    },
+    Hexagon {
+        center: Vec2,
+        radius: f32,
+        vertical: bool,
+    },
+    Poly {
+        center: Vec2,
+        radius: f32,
+        sides: u8,
+        rotation: f32,
+        star: Option<f32>,
+    },
+    PolyLine {
+        center: Vec2,
+        radius: f32,
+        sides: u8,
+        rotation: f32,
+        star: Option<f32>,
+        thickness: f32,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+        thickness: f32,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+    },
+    Path {
+        start: Vec2,
+        segments: Vec<EditorSegments>,
+        thickness: f32,
+        closed: bool,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "EditorElement")]
+struct EditorElementV2 {
+    #[serde(with = "EditorColor")]
+    color: Color,
+    value: EditorValuesV2,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "EditorDocument")]
+struct EditorDocumentV2 {
+    element: EditorElements,
+    snap: bool,
+    grid: u16,
+    stack: Vec<EditorElementV2>,
+    #[serde(default)]
+    export: EditorExport,
+    #[serde(default = "EditorArtboard::defaults")]
+    artboards: Vec<EditorArtboard>,
+}
+
+// Outlines move their radius to the middle of the band they were drawn with,
+// so they keep covering the same pixels with a centered stroke.
+impl From<EditorElementV2> for EditorElement {
+    fn from(element: EditorElementV2) -> Self {
+        let color = element.color;
+        let fill = |value| EditorElement::new(value, Some(color), None, 1.0);
+        let stroke = |value, thickness| EditorElement::new(value, None, Some(color), thickness);
+        match element.value {
+            EditorValuesV2::Line {
+                point_a,
+                point_b,
+                thickness,
+            } => stroke(EditorValues::Line { point_a, point_b }, thickness),
+            EditorValuesV2::Circle { center, radius } => {
+                fill(EditorValues::Circle { center, radius })
+            }
+            EditorValuesV2::CircleLine { center, radius } => {
+                let radius = radius + 0.5;
+                stroke(EditorValues::Circle { center, radius }, 1.0)
+            }
+            EditorValuesV2::Arc {
+                center,
+                radius,
+                thickness,
+                start,
+                sweep,
+            } => {
+                let radius = radius + thickness / 2.0;
+                let value = EditorValues::Arc {
+                    center,
+                    radius,
+                    start,
+                    sweep,
+                };
+                stroke(value, thickness)
+            }
+            EditorValuesV2::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => fill(EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            }),
+            EditorValuesV2::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => fill(EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            }),
+            EditorValuesV2::EllipseLine {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                let value = EditorValues::Ellipse {
+                    center,
+                    width,
+                    height,
+                    rotation,
+                };
+                stroke(value, 1.0)
+            }
+            EditorValuesV2::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => fill(EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            }),
+            EditorValuesV2::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => fill(EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            }),
+            EditorValuesV2::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                // Hexagons had a border of 1.0 in the fill color.
+                let radius = radius + 0.5;
+                let value = EditorValues::Hexagon {
+                    center,
+                    radius,
+                    vertical,
+                };
+                EditorElement::new(value, Some(color), Some(color), 1.0)
+            }
+            EditorValuesV2::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+            } => fill(EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+            }),
+            EditorValuesV2::PolyLine {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+                thickness,
+            } => {
+                // Star outlines were already centered on the edges, the band
+                // of regular ones was thinner across the edges.
+                let (radius, thickness) = match star {
+                    Some(_) => (radius, thickness),
+                    None => (
+                        radius + thickness / 2.0,
+                        thickness * (PI / sides as f32).cos(),
+                    ),
+                };
+                let value = EditorValues::Poly {
+                    center,
+                    radius,
+                    sides,
+                    rotation,
+                    star,
+                };
+                stroke(value, thickness)
+            }
+            EditorValuesV2::Polyline { points, thickness } => {
+                stroke(EditorValues::Polyline { points }, thickness)
+            }
+            EditorValuesV2::Polygon { points } => fill(EditorValues::Polygon { points }),
+            EditorValuesV2::Path {
+                start,
+                segments,
+                thickness,
+                closed,
+            } => {
+                let value = EditorValues::Path {
+                    start,
+                    segments,
+                    closed,
+                };
+                if closed {
+                    fill(value)
+                } else {
+                    stroke(value, thickness)
+                }
+            }
+        }
+    }
+}
+
+impl From<EditorDocumentV2> for EditorDocument {
+    fn from(document: EditorDocumentV2) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            element: document.element,
+            snap: document.snap,
+            grid: document.grid,
+            stack: document
+                .stack
+                .into_iter()
+                .map(EditorElement::from)
+                .collect(),
+            export: document.export,
+            artboards: document.artboards,
+            recent: Vec::new(),
+            palette: EditorPalette::default(),
+            layers: EditorLayer::defaults(),
+        }
+    }
+}
+
+impl EditorDocument {
+    pub fn new(state: &EditorState) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            element: state.element,
+            snap: state.snap,
+            grid: state.grid,
+            stack: state.stack.clone(),
+            export: state.export.clone(),
+            artboards: state.artboards.clone(),
+            recent: state.recent.clone(),
+            palette: state.palette.clone(),
+            layers: state.layers.clone(),
+        }
+    }
+
+    pub fn apply(self, state: &mut EditorState) {
+        state.element = self.element;
+        state.button = Some(self.element.into());
+        state.snap = self.snap;
+        state.grid = self.grid;
+        state.stack = self.stack;
+        state.export = self.export;
+        state.artboards = self.artboards;
+        state.artboard = 0;
+        state.recent = self.recent;
+        state.palette = self.palette;
+        state.layers = self.layers;
+        state.layer = 0;
+        state.layer_drag = None;
+        state.picker = None;
+        state.stack_undo.clear();
+        state.stack_redo.clear();
+        state.current = None;
+        state.points.clear();
+        state.segments.clear();
+        state.tangent = None;
+        state.sweep = 0.0;
+        state.drag = false;
+        state.drag_offset = None;
+        state.drag_stack = None;
+        state.selection.clear();
+        state.marquee = None;
+        state.handle = None;
+        state.vertex = None;
+        state.arrange();
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let EditorDocumentVersion { version } = ron::from_str(content)
+            .map_err(|e| format!("Failed to read document version: {}", e))?;
+
+        match version {
+            DOCUMENT_VERSION => {
+                Ok(ron::from_str(content)
+                    .map_err(|e| format!("Failed to parse document: {}", e))?)
+            }
+            1 | 2 => {
+                let document: EditorDocumentV2 = ron::from_str(content)
+                    .map_err(|e| format!("Failed to parse document: {}", e))?;
+                let mut document = EditorDocument::from(document);
+                if version == 1 {
+                    // The display frames moved from the middle of the window
+                    // to the document origin.
+                    let display = EditorArtboard::defaults()[0].rect().center();
+                    let offset = display - DOCUMENT_WINDOW_V1 / 2.0;
+                    for i in document.stack.iter_mut() {
+                        i.value.translate(offset);
+                    }
+                }
+                Ok(document)
+            }
+            v if v > DOCUMENT_VERSION => Err(format!(
+                "Document version {} is newer than supported version {}",
+                v, DOCUMENT_VERSION
+            )
+            .into()),
+            v => Err(format!("Document version {} is not supported", v).into()),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        EditorDocument::parse(&content).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let content = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|e| format!("Failed to serialize document: {}", e))?;
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+    use macroquad::prelude::RED;
+
+    use super::EditorDocument;
+    use super::DOCUMENT_VERSION;
+    use crate::editor::EditorArtboard;
+    use crate::editor::EditorElement;
+    use crate::editor::EditorElements;
+    use crate::editor::EditorFrames;
+    use crate::editor::EditorValues;
+
+    #[test]
+    fn version_one_moves_to_the_document_origin() {
+        let content = "(version: 1, element: Circle, snap: true, grid: 1, stack: [\
+                       (color: (r: 0.9, g: 0.16, b: 0.22, a: 1.0), \
+                       value: Circle(center: (80.0, 60.0), radius: 5.0))])";
+
+        let document = EditorDocument::parse(content).unwrap();
+        assert_eq!(document.version, DOCUMENT_VERSION);
+        let value = EditorValues::Circle {
+            center: Vec2::new(0.0, 0.0),
+            radius: 5.0,
+        };
+        assert_eq!(
+            document.stack[0],
+            EditorElement::new(value, Some(RED), None, 1.0)
+        );
+    }
+
+    #[test]
+    fn version_two_outlines_become_strokes() {
+        let content = "(version: 2, element: CircleLine, snap: true, grid: 1, stack: [\
+                       (color: (r: 0.9, g: 0.16, b: 0.22, a: 1.0), \
+                       value: CircleLine(center: (0.0, 0.0), radius: 10.0)), \
+                       (color: (r: 0.9, g: 0.16, b: 0.22, a: 1.0), \
+                       value: Arc(center: (0.0, 0.0), radius: 10.0, thickness: 4.0, \
+                       start: 0.0, sweep: 90.0)), \
+                       (color: (r: 0.9, g: 0.16, b: 0.22, a: 1.0), \
+                       value: PolyLine(center: (0.0, 0.0), radius: 10.0, sides: 4, \
+                       rotation: 0.0, star: None, thickness: 2.0))])";
+
+        let document = EditorDocument::parse(content).unwrap();
+        assert_eq!(document.element, EditorElements::Circle);
+        let value = EditorValues::Circle {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.5,
+        };
+        assert_eq!(
+            document.stack[0],
+            EditorElement::new(value, None, Some(RED), 1.0)
+        );
+        let value = EditorValues::Arc {
+            center: Vec2::new(0.0, 0.0),
+            radius: 12.0,
+            start: 0.0,
+            sweep: 90.0,
+        };
+        assert_eq!(
+            document.stack[1],
+            EditorElement::new(value, None, Some(RED), 4.0)
+        );
+        // The band of the outline keeps its inner edge at the radius.
+        let poly = &document.stack[2];
+        assert!(poly.hit(Vec2::new(10.2, 0.0), 0.0));
+        assert!(!poly.hit(Vec2::new(9.8, 0.0), 0.0));
+    }
+
+    #[test]
+    fn documents_without_artboards_get_the_display_frames() {
+        let content = "(version: 2, element: Line, snap: true, grid: 1, stack: [], \
+                       export: (frame: DisplayHd))";
+        let document = EditorDocument::parse(content).unwrap();
+        assert_eq!(document.artboards, EditorArtboard::defaults());
+        assert_eq!(document.export.frame, EditorFrames::Artboard(1));
+    }
+}
@@ -1,37 +1,61 @@
+use macroquad::prelude::draw_arc;
 use macroquad::prelude::draw_circle;
 use macroquad::prelude::draw_ellipse;
 use macroquad::prelude::draw_hexagon;
 use macroquad::prelude::draw_line;
+use macroquad::prelude::draw_poly;
+use macroquad::prelude::draw_poly_lines;
 use macroquad::prelude::draw_rectangle_ex;
+use macroquad::prelude::draw_rectangle_lines_ex;
 use macroquad::prelude::draw_triangle;
 use macroquad::prelude::Color;
 use macroquad::prelude::DrawRectangleParams;
+use macroquad::prelude::Rect;
 use macroquad::prelude::Vec2;
 use macroquad::shapes::draw_circle_lines;
 use macroquad::shapes::draw_ellipse_lines;
+use serde::Deserialize;
+use serde::Serialize;
+use std::f32::consts::PI;
 
-#[derive(Debug, Clone, Copy)]
+use super::EditorColorOption;
+use super::EditorSegments;
+
+// Side counts of regular polygons and stars.
+pub const POLY_SIDES_MIN: u8 = 3;
+pub const POLY_SIDES_MAX: u8 = 64;
+// Inner radius of a new star relative to its outer radius.
+pub const POLY_STAR: f32 = 0.5;
+// Stroke widths reachable with the thickness controls.
+pub const THICKNESS_MIN: f32 = 0.5;
+pub const THICKNESS_MAX: f32 = 64.0;
+pub const THICKNESS_STEP: f32 = 0.5;
+
+// Outlines of the shapes, how they are painted is up to `EditorElement`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EditorValues {
     Line {
         point_a: Vec2,
         point_b: Vec2,
-        thickness: f32,
     },
     Circle {
         center: Vec2,
         radius: f32,
     },
-    CircleLine {
+    // Arcs span `sweep` degrees from `start`.
+    Arc {
         center: Vec2,
         radius: f32,
+        start: f32,
+        sweep: f32,
     },
-    Ellipse {
+    Pie {
         center: Vec2,
-        width: f32,
-        height: f32,
-        rotation: f32,
+        radius: f32,
+        start: f32,
+        sweep: f32,
     },
-    EllipseLine {
+    Ellipse {
         center: Vec2,
         width: f32,
         height: f32,
@@ -53,11 +77,46 @@ pub enum EditorValues {
         radius: f32,
         vertical: bool,
     },
+    // Regular polygons with the first corner at `rotation` degrees, stars add
+    // an inner corner at `star` times the radius between each pair.
+    Poly {
+        center: Vec2,
+        radius: f32,
+        sides: u8,
+        rotation: f32,
+        star: Option<f32>,
+    },
+    Polyline {
+        points: Vec<Vec2>,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+    },
+    Path {
+        start: Vec2,
+        segments: Vec<EditorSegments>,
+        closed: bool,
+    },
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EditorElement {
-    pub color: Color,
+    // Lines, polylines, arcs and open paths have no inside and are only
+    // stroked.
+    #[serde(with = "EditorColorOption")]
+    pub fill: Option<Color>,
+    // Strokes are centered on the outline.
+    #[serde(with = "EditorColorOption")]
+    pub stroke: Option<Color>,
+    // Palette entries the colors come from, exported by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_swatch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_swatch: Option<String>,
+    pub thickness: f32,
+    // Id of the layer holding the element.
+    #[serde(default)]
+    pub layer: usize,
     pub value: EditorValues,
     // use super::EditorShapes;
 
@@ -77,70 +136,987 @@ pub struct EditorElement {
     // }
 }
 
-impl EditorElement {
-    pub fn new(value: EditorValues, color: Color) -> Self {
-        Self { color, value }
+impl EditorValues {
+    // Bounds of the outline, strokes reach past it.
+    pub fn bounds(&self) -> Rect {
+        let rect = |points: &[Vec2]| {
+            let min = points.iter().fold(Vec2::MAX, |a, b| a.min(*b));
+            let max = points.iter().fold(Vec2::MIN, |a, b| a.max(*b));
+            Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+        };
+        match *self {
+            EditorValues::Line { point_a, point_b } => rect(&[point_a, point_b]),
+            EditorValues::Circle { center, radius } => Rect::new(
+                center.x - radius,
+                center.y - radius,
+                radius * 2.0,
+                radius * 2.0,
+            ),
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let (from, span) = EditorValues::span(start, sweep);
+                let points: Vec<Vec2> = EditorValues::extremes(from, span)
+                    .iter()
+                    .map(|i| center + Vec2::from_angle(i.to_radians()) * radius)
+                    .collect();
+                rect(&points)
+            }
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let (from, span) = EditorValues::span(start, sweep);
+                let mut points: Vec<Vec2> = EditorValues::extremes(from, span)
+                    .iter()
+                    .map(|i| center + Vec2::from_angle(i.to_radians()) * radius)
+                    .collect();
+                points.push(center);
+                rect(&points)
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                // Ellipses are drawn with `width` and `height` as half axes
+                // and `rotation` in degrees.
+                let rotation = rotation.to_radians();
+                let (sin, cos) = rotation.sin_cos();
+                let x = ((width * cos).powi(2) + (height * sin).powi(2)).sqrt();
+                let y = ((width * sin).powi(2) + (height * cos).powi(2)).sqrt();
+                Rect::new(center.x - x, center.y - y, x * 2.0, y * 2.0)
+            }
+            EditorValues::Polyline { ref points } => rect(points),
+            EditorValues::Path { .. } => self.flatten().bounds(),
+            _ => rect(&self.corners()),
+        }
     }
-    pub fn draw(&self, color: Option<Color>) {
-        let color = color.unwrap_or(self.color);
-        match self.value {
+
+    // Outline points of polygonal shapes in drawing order, empty for curves and lines.
+    pub fn corners(&self) -> Vec<Vec2> {
+        match *self {
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                // Rectangles rotate around their top-left `point`.
+                let (sin, cos) = rotation.sin_cos();
+                [
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(width, 0.0),
+                    Vec2::new(width, height),
+                    Vec2::new(0.0, height),
+                ]
+                .iter()
+                .map(|i| point + Vec2::new(i.x * cos - i.y * sin, i.x * sin + i.y * cos))
+                .collect()
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => vec![point_a, point_b, point_c],
+            EditorValues::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                let rotation: f32 = if vertical { 90.0 } else { 0.0 };
+                (0..6)
+                    .map(|i| {
+                        let angle = (i as f32 * 60.0 + rotation).to_radians();
+                        center + Vec2::new(angle.cos(), angle.sin()) * radius
+                    })
+                    .collect()
+            }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+            } => EditorValues::poly(center, radius, sides, rotation, star),
+            EditorValues::Polygon { ref points } => points.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Whether `position` lies inside the shape, open shapes have no inside.
+    pub fn contains(&self, position: Vec2) -> bool {
+        match *self {
+            EditorValues::Circle { center, radius } => position.distance(center) <= radius,
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                position.distance(center) <= radius
+                    && EditorValues::within(position - center, start, sweep)
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                let p = EditorValues::local(position - center, rotation.to_radians());
+                (p.x / width).powi(2) + (p.y / height).powi(2) <= 1.0
+            }
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                let p = EditorValues::local(position - point, rotation);
+                p.x >= 0.0 && p.x <= width && p.y >= 0.0 && p.y <= height
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => {
+                let area = (point_b - point_a).perp_dot(point_c - point_a);
+                if area.abs() <= f32::EPSILON {
+                    return false;
+                }
+                let b = (position - point_a).perp_dot(point_c - point_a) / area;
+                let c = (point_b - point_a).perp_dot(position - point_a) / area;
+                b >= 0.0 && c >= 0.0 && b + c <= 1.0
+            }
+            EditorValues::Hexagon { .. } => EditorValues::inside(&self.corners(), position),
+            EditorValues::Poly { center, .. } => EditorValues::fan(center, &self.corners())
+                .iter()
+                .any(|i| EditorValues::inside(i, position)),
+            EditorValues::Polygon { ref points } => EditorValues::triangulate(points)
+                .iter()
+                .any(|i| EditorValues::inside(i, position)),
+            EditorValues::Path { closed: true, .. } => self.flatten().contains(position),
+            _ => false,
+        }
+    }
+
+    // Pieces with closed form `contains` tests covering the inside, so many
+    // positions can be tested without tessellating for each.
+    pub fn fills(&self) -> Vec<EditorValues> {
+        let triangles = match *self {
+            EditorValues::Hexagon { center, .. } | EditorValues::Poly { center, .. } => {
+                EditorValues::fan(center, &self.corners())
+            }
+            EditorValues::Polygon { ref points } => EditorValues::triangulate(points),
+            EditorValues::Path { closed: true, .. } => return self.flatten().fills(),
+            _ if self.closed() => return vec![self.clone()],
+            _ => return Vec::new(),
+        };
+        triangles
+            .iter()
+            .map(|[a, b, c]| EditorValues::Triangle {
+                point_a: *a,
+                point_b: *b,
+                point_c: *c,
+            })
+            .collect()
+    }
+
+    // Pieces with closed form `stroke` distances covering the outline.
+    pub fn strokes(&self) -> Vec<EditorValues> {
+        match self {
+            EditorValues::Line { .. }
+            | EditorValues::Circle { .. }
+            | EditorValues::Arc { .. }
+            | EditorValues::Ellipse { .. }
+            | EditorValues::Rectangle { .. } => vec![self.clone()],
+            _ => EditorValues::segments(&self.outline()),
+        }
+    }
+
+    // Whether the shape has an inside that can be filled.
+    pub fn closed(&self) -> bool {
+        match self {
+            EditorValues::Line { .. }
+            | EditorValues::Arc { .. }
+            | EditorValues::Polyline { .. } => false,
+            EditorValues::Path { closed, .. } => *closed,
+            _ => true,
+        }
+    }
+
+    // Distance from `position` to a stroke of `thickness` as it is drawn,
+    // negative inside the stroke.
+    pub fn stroke(&self, position: Vec2, thickness: f32) -> f32 {
+        let half = thickness / 2.0;
+        match *self {
+            EditorValues::Line { point_a, point_b } => {
+                // Lines are drawn as quads without caps past their end points.
+                let direction = point_b - point_a;
+                let length = direction.length();
+                if length <= f32::EPSILON {
+                    return position.distance(point_a) - half;
+                }
+                let along = (position - point_a).dot(direction) / length;
+                let across = direction.perp_dot(position - point_a).abs() / length - half;
+                let outside = (-along).max(along - length).max(0.0);
+                if outside > 0.0 {
+                    Vec2::new(outside, across.max(0.0)).length()
+                } else {
+                    across
+                }
+            }
+            EditorValues::Circle { center, radius } => {
+                (position.distance(center) - radius).abs() - half
+            }
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                if EditorValues::within(position - center, start, sweep) {
+                    return (position.distance(center) - radius).abs() - half;
+                }
+                // Outside of the sweep the closest point is on one of the ends.
+                [start, start + sweep]
+                    .iter()
+                    .map(|i| {
+                        let direction = Vec2::from_angle(i.to_radians());
+                        let t = (position - center)
+                            .dot(direction)
+                            .clamp(radius - half, radius + half);
+                        position.distance(center + direction * t)
+                    })
+                    .fold(f32::INFINITY, f32::min)
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                // Distance to the outline is approximated from the implicit
+                // ellipse equation and its gradient.
+                let p = EditorValues::local(position - center, rotation.to_radians());
+                let value = (p.x / width).powi(2) + (p.y / height).powi(2) - 1.0;
+                let gradient =
+                    Vec2::new(2.0 * p.x / width.powi(2), 2.0 * p.y / height.powi(2)).length();
+                if gradient <= f32::EPSILON {
+                    return f32::INFINITY;
+                }
+                (value / gradient).abs() - half
+            }
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                // Signed distance to the edges of the rectangle.
+                let size = Vec2::new(width, height) / 2.0;
+                let p = EditorValues::local(position - point, rotation) - size;
+                let q = p.abs() - size;
+                let distance = q.max(Vec2::ZERO).length() + q.x.max(q.y).min(0.0);
+                distance.abs() - half
+            }
+            _ => EditorValues::segments(&self.outline())
+                .iter()
+                .map(|i| i.stroke(position, thickness))
+                .fold(f32::INFINITY, f32::min),
+        }
+    }
+
+    pub fn translate(&mut self, offset: Vec2) {
+        match self {
+            EditorValues::Line {
+                point_a, point_b, ..
+            } => {
+                *point_a += offset;
+                *point_b += offset;
+            }
+            EditorValues::Circle { center, .. }
+            | EditorValues::Arc { center, .. }
+            | EditorValues::Pie { center, .. }
+            | EditorValues::Ellipse { center, .. }
+            | EditorValues::Hexagon { center, .. }
+            | EditorValues::Poly { center, .. } => {
+                *center += offset;
+            }
+            EditorValues::Rectangle { point, .. } => {
+                *point += offset;
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => {
+                *point_a += offset;
+                *point_b += offset;
+                *point_c += offset;
+            }
+            EditorValues::Polyline { points } | EditorValues::Polygon { points } => {
+                for i in points.iter_mut() {
+                    *i += offset;
+                }
+            }
+            EditorValues::Path {
+                start, segments, ..
+            } => {
+                *start += offset;
+                for i in segments.iter_mut() {
+                    i.map(|p| p + offset);
+                }
+            }
+        }
+    }
+
+    // Scales the shape by `factor` along the axes of a frame turned by
+    // `rotation` radians around `anchor`. Circles and shapes not aligned with
+    // the frame keep their proportions and use the average factor.
+    pub fn scale(&mut self, anchor: Vec2, factor: Vec2, rotation: f32) {
+        let transform = |p: Vec2| {
+            let local = EditorValues::local(p - anchor, rotation) * factor;
+            anchor + EditorValues::local(local, -rotation)
+        };
+        let average = (factor.x.abs() + factor.y.abs()) / 2.0;
+        // Factors along the shape axes when they line up with the frame.
+        let aligned = |angle: f32| {
+            let (sin, cos) = (angle - rotation).sin_cos();
+            if sin.abs() < 0.001 {
+                Some(factor.abs())
+            } else if cos.abs() < 0.001 {
+                Some(Vec2::new(factor.y.abs(), factor.x.abs()))
+            } else {
+                None
+            }
+        };
+        match self {
+            EditorValues::Line {
+                point_a, point_b, ..
+            } => {
+                *point_a = transform(*point_a);
+                *point_b = transform(*point_b);
+            }
+            EditorValues::Circle { center, radius }
+            | EditorValues::Arc { center, radius, .. }
+            | EditorValues::Pie { center, radius, .. }
+            | EditorValues::Hexagon { center, radius, .. }
+            | EditorValues::Poly { center, radius, .. } => {
+                *center = transform(*center);
+                *radius *= average;
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                let factor = aligned(rotation.to_radians()).unwrap_or(Vec2::splat(average));
+                *center = transform(*center);
+                *width *= factor.x;
+                *height *= factor.y;
+            }
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                let factor = aligned(*rotation).unwrap_or(Vec2::splat(average));
+                *point = transform(*point);
+                *width *= factor.x;
+                *height *= factor.y;
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => {
+                *point_a = transform(*point_a);
+                *point_b = transform(*point_b);
+                *point_c = transform(*point_c);
+            }
+            EditorValues::Polyline { points } | EditorValues::Polygon { points } => {
+                for i in points.iter_mut() {
+                    *i = transform(*i);
+                }
+            }
+            EditorValues::Path {
+                start, segments, ..
+            } => {
+                *start = transform(*start);
+                for i in segments.iter_mut() {
+                    i.map(transform);
+                }
+            }
+        }
+    }
+
+    // Rotates the shape by `angle` radians around `pivot`.
+    pub fn rotate(&mut self, pivot: Vec2, angle: f32) {
+        let transform = |p: Vec2| pivot + EditorValues::local(p - pivot, -angle);
+        match self {
             EditorValues::Line {
+                point_a, point_b, ..
+            } => {
+                *point_a = transform(*point_a);
+                *point_b = transform(*point_b);
+            }
+            EditorValues::Circle { center, .. } => {
+                *center = transform(*center);
+            }
+            // Hexagons only turn by 30 degree steps, other angles make them
+            // a regular polygon.
+            EditorValues::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                let base: f32 = if *vertical { 90.0 } else { 0.0 };
+                let rotation = base + angle.to_degrees();
+                let step = rotation.rem_euclid(30.0);
+                let center = transform(*center);
+                *self = if step.min(30.0 - step) < 0.01 {
+                    EditorValues::Hexagon {
+                        center,
+                        radius: *radius,
+                        vertical: (rotation / 30.0).round().rem_euclid(2.0) == 1.0,
+                    }
+                } else {
+                    EditorValues::Poly {
+                        center,
+                        radius: *radius,
+                        sides: 6,
+                        rotation,
+                        star: None,
+                    }
+                };
+            }
+            EditorValues::Ellipse {
+                center, rotation, ..
+            }
+            | EditorValues::Arc {
+                center,
+                start: rotation,
+                ..
+            }
+            | EditorValues::Pie {
+                center,
+                start: rotation,
+                ..
+            }
+            | EditorValues::Poly {
+                center, rotation, ..
+            } => {
+                *center = transform(*center);
+                *rotation += angle.to_degrees();
+            }
+            EditorValues::Rectangle {
+                point, rotation, ..
+            } => {
+                *point = transform(*point);
+                *rotation += angle;
+            }
+            EditorValues::Triangle {
                 point_a,
                 point_b,
-                thickness,
-            } => {
-                let a_x = point_a.x;
-                let a_y = point_a.y;
-                let b_x = point_b.x;
-                let b_y = point_b.y;
-                draw_line(a_x, a_y, b_x, b_y, thickness, color);
-            }
-            // EditorValues::Poly => {
-            //     let radius = current.distance(position);
-            //     let sides = (radius / 10.0).clamp(3.0, 12.0) as u8;
-            //     let dx = position.x - current.x;
-            //     let dy = position.y - current.y;
-            //     let rotation = dy.atan2(dx);
-            //     draw_poly(current.x, current.y, sides, radius, rotation, color);
-            // }
+                point_c,
+            } => {
+                *point_a = transform(*point_a);
+                *point_b = transform(*point_b);
+                *point_c = transform(*point_c);
+            }
+            EditorValues::Polyline { points } | EditorValues::Polygon { points } => {
+                for i in points.iter_mut() {
+                    *i = transform(*i);
+                }
+            }
+            EditorValues::Path {
+                start, segments, ..
+            } => {
+                *start = transform(*start);
+                for i in segments.iter_mut() {
+                    i.map(transform);
+                }
+            }
+        }
+    }
+
+    // Control points shown and dragged in vertex mode.
+    pub fn points(&self) -> Vec<Vec2> {
+        match *self {
+            EditorValues::Line { point_a, point_b } => vec![point_a, point_b],
             EditorValues::Circle { center, radius } => {
-                let x = center.x;
-                let y = center.y;
-                draw_circle(x, y, radius, color);
-            }
-            EditorValues::CircleLine { center, radius } => {
-                let x = center.x;
-                let y = center.y;
-                draw_circle_lines(x, y, radius, 1.0, color);
-            }
-            // EditorElements::Arc => {
-            //     let radius = current.distance(position);
-            //     let sides = (radius / 4.0).clamp(12.0, 64.0) as u8;
-            //     let dx = position.x - current.x;
-            //     let dy = position.y - current.y;
-            //     let angle = dy.atan2(dx).to_degrees();
-            //     let arc = 180.0;
-            //     draw_arc(current.x, current.y, sides, arc, angle, 1.0, radius, color);
-            // }
+                vec![center, center + Vec2::new(radius, 0.0)]
+            }
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            }
+            | EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => vec![
+                center,
+                center + Vec2::from_angle(start.to_radians()) * radius,
+                center + Vec2::from_angle((start + sweep).to_radians()) * radius,
+            ],
             EditorValues::Ellipse {
                 center,
                 width,
                 height,
                 rotation,
             } => {
-                let x = center.x;
-                let y = center.y;
-                draw_ellipse(x, y, width, height, rotation, color);
+                let rotation = rotation.to_radians();
+                vec![
+                    center,
+                    center + EditorValues::local(Vec2::new(width, 0.0), -rotation),
+                    center + EditorValues::local(Vec2::new(0.0, height), -rotation),
+                ]
+            }
+            EditorValues::Hexagon { center, .. } => {
+                let mut points = vec![center];
+                points.extend(self.corners().first());
+                points
+            }
+            EditorValues::Poly { center, star, .. } => {
+                // Stars also expose their first inner corner for the ratio.
+                let count = if star.is_some() { 2 } else { 1 };
+                let mut points = vec![center];
+                points.extend(self.corners().into_iter().take(count));
+                points
+            }
+            EditorValues::Rectangle { .. } | EditorValues::Triangle { .. } => self.corners(),
+            EditorValues::Polyline { ref points } | EditorValues::Polygon { ref points } => {
+                points.clone()
             }
-            EditorValues::EllipseLine {
+            EditorValues::Path {
+                start,
+                ref segments,
+                ..
+            } => EditorSegments::points(start, segments),
+        }
+    }
+
+    // Moves the control point `index` from `points` to `position`.
+    pub fn set_point(&mut self, index: usize, position: Vec2) {
+        match self {
+            EditorValues::Line {
+                point_a, point_b, ..
+            } => match index {
+                0 => *point_a = position,
+                _ => *point_b = position,
+            },
+            EditorValues::Circle { center, radius }
+            | EditorValues::Hexagon { center, radius, .. } => match index {
+                0 => *center = position,
+                _ => *radius = center.distance(position),
+            },
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            }
+            | EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let offset = position - *center;
+                let angle = offset.y.atan2(offset.x).to_degrees();
+                match index {
+                    0 => *center = position,
+                    // Moving the start keeps the end of the sweep in place.
+                    1 => {
+                        let end = *start + *sweep;
+                        *radius = offset.length();
+                        *sweep = EditorValues::sweep(end - angle, *sweep);
+                        *start = angle;
+                    }
+                    _ => {
+                        *radius = offset.length();
+                        *sweep = EditorValues::sweep(angle - *start, *sweep);
+                    }
+                }
+            }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+            } => {
+                let offset = position - *center;
+                match index {
+                    0 => *center = position,
+                    1 => {
+                        *radius = offset.length();
+                        *rotation = offset.y.atan2(offset.x).to_degrees();
+                    }
+                    _ => {
+                        if let Some(star) = star {
+                            // The inner corner sits halfway between two outer ones.
+                            let angle = (*rotation + 180.0 / *sides as f32).to_radians();
+                            let inner = offset.dot(Vec2::from_angle(angle));
+                            *star = (inner / radius.max(f32::EPSILON)).clamp(0.05, 1.0);
+                        }
+                    }
+                }
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                let offset = position - *center;
+                match index {
+                    0 => *center = position,
+                    1 => {
+                        *width = offset.length();
+                        *rotation = offset.y.atan2(offset.x).to_degrees();
+                    }
+                    _ => {
+                        *height = offset.length();
+                        *rotation = offset.y.atan2(offset.x).to_degrees() - 90.0;
+                    }
+                }
+            }
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                // The opposite corner stays in place.
+                let size = Vec2::new(*width, *height);
+                let corner = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y][(index + 2) % 4];
+                let anchor = *point + EditorValues::local(corner * size, -*rotation);
+                let offset = EditorValues::local(position - anchor, *rotation);
+                *point = anchor + EditorValues::local(offset.min(Vec2::ZERO), -*rotation);
+                *width = offset.x.abs();
+                *height = offset.y.abs();
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => match index {
+                0 => *point_a = position,
+                1 => *point_b = position,
+                _ => *point_c = position,
+            },
+            EditorValues::Polyline { points } | EditorValues::Polygon { points } => {
+                if let Some(point) = points.get_mut(index) {
+                    *point = position;
+                }
+            }
+            EditorValues::Path {
+                start,
+                segments,
+                closed,
+            } => EditorSegments::set_point(start, segments, *closed, index, position),
+        }
+    }
+
+    // Rotates `offset` back into the unrotated frame of a shape.
+    pub fn local(offset: Vec2, rotation: f32) -> Vec2 {
+        let (sin, cos) = rotation.sin_cos();
+        Vec2::new(
+            offset.x * cos + offset.y * sin,
+            -offset.x * sin + offset.y * cos,
+        )
+    }
+
+    // Path as the polygon or polyline it is drawn with.
+    pub fn flatten(&self) -> EditorValues {
+        match *self {
+            EditorValues::Path {
+                start,
+                ref segments,
+                closed,
+            } => {
+                let mut points = EditorSegments::flatten(start, segments);
+                if closed {
+                    // The last segment ends on the start again.
+                    if points.len() > 1 && points.first() == points.last() {
+                        points.pop();
+                    }
+                    EditorValues::Polygon { points }
+                } else {
+                    EditorValues::Polyline { points }
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    // Start and length of a sweep turned to run in the positive direction.
+    pub fn span(start: f32, sweep: f32) -> (f32, f32) {
+        let span = sweep.abs().min(360.0);
+        if sweep < 0.0 {
+            (start + sweep, span)
+        } else {
+            (start, span)
+        }
+    }
+
+    // Whether the direction of `offset` lies within the sweep.
+    fn within(offset: Vec2, start: f32, sweep: f32) -> bool {
+        let (from, span) = EditorValues::span(start, sweep);
+        let angle = offset.y.atan2(offset.x).to_degrees();
+        (angle - from).rem_euclid(360.0) <= span || span >= 360.0
+    }
+
+    // End angles of a sweep and the axis directions it passes.
+    fn extremes(from: f32, span: f32) -> Vec<f32> {
+        let mut angles = vec![from, from + span];
+        angles.extend(
+            [0.0, 90.0, 180.0, 270.0]
+                .into_iter()
+                .filter(|i: &f32| (i - from).rem_euclid(360.0) <= span),
+        );
+        angles
+    }
+
+    // Sweep of `angle` degrees wrapped to keep the direction of `previous`.
+    fn sweep(angle: f32, previous: f32) -> f32 {
+        let angle = angle.rem_euclid(360.0);
+        if previous < 0.0 {
+            angle - 360.0
+        } else {
+            angle
+        }
+    }
+
+    // Sides used for a full circle, matching the arc drawing.
+    pub fn sides(radius: f32) -> u8 {
+        (radius / 4.0).clamp(12.0, 64.0) as u8
+    }
+
+    // Corners of a regular polygon or star, the first at `rotation` degrees.
+    pub fn poly(
+        center: Vec2,
+        radius: f32,
+        sides: u8,
+        rotation: f32,
+        star: Option<f32>,
+    ) -> Vec<Vec2> {
+        let corner = |angle: f32, radius: f32| {
+            center + Vec2::from_angle((angle + rotation).to_radians()) * radius
+        };
+        let sides = sides as usize;
+        match star {
+            Some(star) => (0..sides * 2)
+                .map(|i| {
+                    let radius = if i % 2 == 0 { radius } else { radius * star };
+                    corner(i as f32 * 180.0 / sides as f32, radius)
+                })
+                .collect(),
+            None => (0..sides)
+                .map(|i| corner(i as f32 * 360.0 / sides as f32, radius))
+                .collect(),
+        }
+    }
+
+    // Triangles from `center` to each edge of an outline around it.
+    pub fn fan(center: Vec2, points: &[Vec2]) -> Vec<[Vec2; 3]> {
+        (0..points.len())
+            .map(|i| [center, points[i], points[(i + 1) % points.len()]])
+            .collect()
+    }
+
+    // Triangle fan of a pie slice.
+    pub fn slices(center: Vec2, radius: f32, start: f32, sweep: f32) -> Vec<[Vec2; 3]> {
+        let (from, span) = EditorValues::span(start, sweep);
+        let count = (EditorValues::sides(radius) as f32 * span / 360.0)
+            .ceil()
+            .max(1.0) as usize;
+        let point = |i: usize| {
+            let angle = (from + span * i as f32 / count as f32).to_radians();
+            center + Vec2::from_angle(angle) * radius
+        };
+        (0..count)
+            .map(|i| [center, point(i), point(i + 1)])
+            .collect()
+    }
+
+    // Center line of strokes drawn as lines, closed outlines end on their
+    // first point again. Curves are split like they are drawn.
+    pub fn outline(&self) -> Vec<Vec2> {
+        let mut points = match *self {
+            EditorValues::Line { point_a, point_b } => return vec![point_a, point_b],
+            EditorValues::Polyline { ref points } => return points.clone(),
+            EditorValues::Path { .. } => return self.flatten().outline(),
+            EditorValues::Circle { center, radius } => {
+                EditorValues::poly(center, radius, EditorValues::sides(radius), 0.0, None)
+            }
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let slices = EditorValues::slices(center, radius, start, sweep);
+                let mut points: Vec<Vec2> = slices.iter().map(|i| i[1]).collect();
+                points.extend(slices.last().map(|i| i[2]));
+                return points;
+            }
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let slices = EditorValues::slices(center, radius, start, sweep);
+                let mut points = vec![center];
+                points.extend(slices.iter().map(|i| i[1]));
+                points.extend(slices.last().map(|i| i[2]));
+                points
+            }
+            EditorValues::Ellipse {
                 center,
                 width,
                 height,
                 rotation,
             } => {
-                let x = center.x;
-                let y = center.y;
-                draw_ellipse_lines(x, y, width, height, rotation, 1.0, color);
+                let rotation = rotation.to_radians();
+                (0..20)
+                    .map(|i| {
+                        let angle = Vec2::from_angle(i as f32 * PI / 10.0);
+                        let point = Vec2::new(angle.x * width, angle.y * height);
+                        center + EditorValues::local(point, -rotation)
+                    })
+                    .collect()
+            }
+            _ => self.corners(),
+        };
+        points.extend(points.first().copied());
+        points
+    }
+
+    // Radius and width passed to `draw_poly_lines` for a stroke of
+    // `thickness` centered on a regular outline, the band it draws is
+    // thinner across the edges than along the corners.
+    pub fn ring(radius: f32, sides: u8, thickness: f32) -> (f32, f32) {
+        let width = thickness / (PI / sides as f32).cos();
+        (radius - width / 2.0, width)
+    }
+
+    // Segments of a polyline as individual lines.
+    fn segments(points: &[Vec2]) -> Vec<EditorValues> {
+        points
+            .windows(2)
+            .map(|i| EditorValues::Line {
+                point_a: i[0],
+                point_b: i[1],
+            })
+            .collect()
+    }
+
+    // Splits a simple polygon into triangles by ear clipping, concave
+    // outlines included.
+    pub fn triangulate(points: &[Vec2]) -> Vec<[Vec2; 3]> {
+        let mut triangles = Vec::new();
+        if points.len() < 3 {
+            return triangles;
+        }
+        // Signed area decides the winding so ears can be told from reflex corners.
+        let area: f32 = points
+            .iter()
+            .enumerate()
+            .map(|(i, a)| a.perp_dot(points[(i + 1) % points.len()]))
+            .sum();
+        let winding = if area < 0.0 { -1.0 } else { 1.0 };
+
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        while indices.len() > 3 {
+            let count = indices.len();
+            let ear = (0..count).find(|i| {
+                let a = points[indices[(i + count - 1) % count]];
+                let b = points[indices[*i]];
+                let c = points[indices[(i + 1) % count]];
+                if (b - a).perp_dot(c - b) * winding <= 0.0 {
+                    return false;
+                }
+                indices.iter().all(|j| {
+                    let p = points[*j];
+                    p == a || p == b || p == c || !EditorValues::inside(&[a, b, c], p)
+                })
+            });
+            // Self intersecting outlines have no ear left, the rest is fanned.
+            let Some(ear) = ear else {
+                break;
+            };
+            let a = points[indices[(ear + count - 1) % count]];
+            let b = points[indices[ear]];
+            let c = points[indices[(ear + 1) % count]];
+            triangles.push([a, b, c]);
+            indices.remove(ear);
+        }
+        for i in 1..indices.len() - 1 {
+            triangles.push([
+                points[indices[0]],
+                points[indices[i]],
+                points[indices[i + 1]],
+            ]);
+        }
+        triangles
+    }
+
+    // Point in convex polygon test, independent of the winding order.
+    fn inside(points: &[Vec2], position: Vec2) -> bool {
+        let mut sign = 0.0;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let side = (b - *a).perp_dot(position - *a);
+            if side.abs() <= f32::EPSILON {
+                continue;
+            }
+            if sign == 0.0 {
+                sign = side.signum();
+            } else if side.signum() != sign {
+                return false;
+            }
+        }
+        !points.is_empty()
+    }
+
+    pub fn draw_fill(&self, color: Color) {
+        match *self {
+            EditorValues::Circle { center, radius } => {
+                draw_circle(center.x, center.y, radius, color);
+            }
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                for [a, b, c] in EditorValues::slices(center, radius, start, sweep) {
+                    draw_triangle(a, b, c, color);
+                }
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                draw_ellipse(center.x, center.y, width, height, rotation, color);
             }
             EditorValues::Rectangle {
                 point,
@@ -148,12 +1124,10 @@ impl EditorElement {
                 height,
                 rotation,
             } => {
-                let x = point.x;
-                let y = point.y;
                 let offset = Vec2::new(0.0, 0.0);
                 draw_rectangle_ex(
-                    x,
-                    y,
+                    point.x,
+                    point.y,
                     width,
                     height,
                     DrawRectangleParams {
@@ -175,10 +1149,549 @@ impl EditorElement {
                 radius,
                 vertical,
             } => {
-                let x = center.x;
-                let y = center.y;
-                draw_hexagon(x, y, radius, 1.0, vertical, color, color);
+                draw_hexagon(center.x, center.y, radius, 0.0, vertical, color, color);
             }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star,
+            } => {
+                if star.is_some() {
+                    for [a, b, c] in EditorValues::fan(center, &self.corners()) {
+                        draw_triangle(a, b, c, color);
+                    }
+                } else {
+                    draw_poly(center.x, center.y, sides, radius, rotation, color);
+                }
+            }
+            EditorValues::Polygon { ref points } => {
+                for [a, b, c] in EditorValues::triangulate(points) {
+                    draw_triangle(a, b, c, color);
+                }
+            }
+            EditorValues::Path { closed: true, .. } => self.flatten().draw_fill(color),
+            _ => {}
         }
     }
+
+    pub fn draw_stroke(&self, color: Color, thickness: f32) {
+        let half = thickness / 2.0;
+        match *self {
+            EditorValues::Circle { center, radius } => {
+                draw_circle_lines(center.x, center.y, radius - half, thickness, color);
+            }
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let sides = EditorValues::sides(radius);
+                let (from, span) = EditorValues::span(start, sweep);
+                let (x, y) = (center.x, center.y);
+                draw_arc(x, y, sides, radius - half, from, thickness, span, color);
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => {
+                let (x, y) = (center.x, center.y);
+                draw_ellipse_lines(x, y, width, height, rotation, thickness, color);
+            }
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                // The lines are drawn inside the rectangle, so it is grown by
+                // half of them.
+                let point = point + EditorValues::local(Vec2::splat(-half), -rotation);
+                let offset = Vec2::new(0.0, 0.0);
+                draw_rectangle_lines_ex(
+                    point.x,
+                    point.y,
+                    width + thickness,
+                    height + thickness,
+                    thickness,
+                    DrawRectangleParams {
+                        color,
+                        rotation,
+                        offset,
+                    },
+                );
+            }
+            EditorValues::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                let (radius, width) = EditorValues::ring(radius, 6, thickness);
+                let rotation = if vertical { 90.0 } else { 0.0 };
+                draw_poly_lines(center.x, center.y, 6, radius, rotation, width, color);
+            }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star: None,
+            } => {
+                let (radius, width) = EditorValues::ring(radius, sides, thickness);
+                draw_poly_lines(center.x, center.y, sides, radius, rotation, width, color);
+            }
+            _ => {
+                for i in self.outline().windows(2) {
+                    draw_line(i[0].x, i[0].y, i[1].x, i[1].y, thickness, color);
+                }
+            }
+        }
+    }
+}
+
+impl EditorElement {
+    pub fn new(
+        value: EditorValues,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+        thickness: f32,
+    ) -> Self {
+        let fill = fill.filter(|_| value.closed());
+        Self {
+            fill,
+            stroke,
+            fill_swatch: None,
+            stroke_swatch: None,
+            thickness,
+            layer: 0,
+            value,
+        }
+    }
+
+    // Area painted by the fill and the stroke.
+    pub fn bounds(&self) -> Rect {
+        let bounds = self.value.bounds();
+        if self.stroke.is_none() {
+            return bounds;
+        }
+        let half = self.thickness / 2.0;
+        Rect::new(
+            bounds.x - half,
+            bounds.y - half,
+            bounds.w + self.thickness,
+            bounds.h + self.thickness,
+        )
+    }
+
+    // Whether a click at `position` picks the element, strokes accept clicks
+    // within `tolerance`.
+    pub fn hit(&self, position: Vec2, tolerance: f32) -> bool {
+        self.fill.is_some() && self.value.contains(position)
+            || self.stroke.is_some() && self.value.stroke(position, self.thickness) <= tolerance
+    }
+
+    // Fill first and stroke on top, `color` replaces both.
+    pub fn draw(&self, color: Option<Color>) {
+        if let Some(fill) = self.fill {
+            self.value.draw_fill(color.unwrap_or(fill));
+        }
+        if let Some(stroke) = self.stroke {
+            self.value
+                .draw_stroke(color.unwrap_or(stroke), self.thickness);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+    use macroquad::prelude::RED;
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::EditorElement;
+    use super::EditorValues;
+
+    fn filled(value: EditorValues) -> EditorElement {
+        EditorElement::new(value, Some(RED), None, 1.0)
+    }
+
+    fn stroked(value: EditorValues, thickness: f32) -> EditorElement {
+        EditorElement::new(value, None, Some(RED), thickness)
+    }
+
+    #[test]
+    fn line_hits_by_distance_to_segment() {
+        let value = EditorValues::Line {
+            point_a: Vec2::new(0.0, 0.0),
+            point_b: Vec2::new(100.0, 0.0),
+        };
+        let value = stroked(value, 4.0);
+        assert!(value.hit(Vec2::new(50.0, 1.9), 0.0));
+        assert!(!value.hit(Vec2::new(50.0, 2.1), 0.0));
+        assert!(value.hit(Vec2::new(50.0, 4.9), 3.0));
+        assert!(value.hit(Vec2::new(102.0, 0.0), 3.0));
+        assert!(!value.hit(Vec2::new(106.0, 0.0), 3.0));
+    }
+
+    #[test]
+    fn circle_hits_interior() {
+        let value = filled(EditorValues::Circle {
+            center: Vec2::new(10.0, 10.0),
+            radius: 5.0,
+        });
+        assert!(value.hit(Vec2::new(10.0, 10.0), 3.0));
+        assert!(value.hit(Vec2::new(14.9, 10.0), 3.0));
+        assert!(!value.hit(Vec2::new(15.1, 10.0), 3.0));
+    }
+
+    #[test]
+    fn circle_stroke_hits_outline_only() {
+        let value = EditorValues::Circle {
+            center: Vec2::new(0.0, 0.0),
+            radius: 20.0,
+        };
+        let value = stroked(value, 1.0);
+        assert!(value.hit(Vec2::new(20.4, 0.0), 0.0));
+        assert!(value.hit(Vec2::new(0.0, -22.0), 3.0));
+        assert!(!value.hit(Vec2::new(0.0, 0.0), 3.0));
+        assert!(!value.hit(Vec2::new(10.0, 0.0), 3.0));
+    }
+
+    #[test]
+    fn ellipse_hits_rotated_interior() {
+        let value = filled(EditorValues::Ellipse {
+            center: Vec2::new(0.0, 0.0),
+            width: 20.0,
+            height: 5.0,
+            rotation: 90.0,
+        });
+        assert!(value.hit(Vec2::new(0.0, 19.0), 0.0));
+        assert!(!value.hit(Vec2::new(19.0, 0.0), 0.0));
+    }
+
+    #[test]
+    fn ellipse_stroke_hits_outline_only() {
+        let value = EditorValues::Ellipse {
+            center: Vec2::new(0.0, 0.0),
+            width: 20.0,
+            height: 10.0,
+            rotation: 0.0,
+        };
+        let value = stroked(value, 1.0);
+        assert!(value.hit(Vec2::new(20.0, 0.0), 0.0));
+        assert!(value.hit(Vec2::new(0.0, 12.0), 3.0));
+        assert!(!value.hit(Vec2::new(0.0, 0.0), 3.0));
+        assert!(!value.hit(Vec2::new(0.0, 15.0), 3.0));
+    }
+
+    #[test]
+    fn rectangle_hits_from_top_left_pivot() {
+        let value = filled(EditorValues::Rectangle {
+            point: Vec2::new(10.0, 10.0),
+            width: 40.0,
+            height: 20.0,
+            rotation: 0.0,
+        });
+        assert!(value.hit(Vec2::new(45.0, 25.0), 0.0));
+        assert!(!value.hit(Vec2::new(5.0, 5.0), 0.0));
+
+        let value = filled(EditorValues::Rectangle {
+            point: Vec2::new(10.0, 10.0),
+            width: 40.0,
+            height: 20.0,
+            rotation: FRAC_PI_2,
+        });
+        assert!(value.hit(Vec2::new(0.0, 45.0), 0.0));
+        assert!(!value.hit(Vec2::new(45.0, 15.0), 0.0));
+    }
+
+    #[test]
+    fn triangle_hits_by_barycentric_test() {
+        let value = filled(EditorValues::Triangle {
+            point_a: Vec2::new(0.0, 0.0),
+            point_b: Vec2::new(10.0, 0.0),
+            point_c: Vec2::new(0.0, 10.0),
+        });
+        assert!(value.hit(Vec2::new(2.0, 2.0), 3.0));
+        assert!(value.hit(Vec2::new(5.0, 5.0), 3.0));
+        assert!(!value.hit(Vec2::new(6.0, 6.0), 3.0));
+        assert!(!value.hit(Vec2::new(-1.0, 5.0), 3.0));
+    }
+
+    #[test]
+    fn hexagon_hits_true_boundary() {
+        let value = filled(EditorValues::Hexagon {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.0,
+            vertical: false,
+        });
+        // Corners point along the x axis, flat edges face the y axis.
+        assert!(value.hit(Vec2::new(9.5, 0.0), 0.0));
+        assert!(value.hit(Vec2::new(0.0, 8.5), 0.0));
+        assert!(!value.hit(Vec2::new(0.0, 9.5), 0.0));
+        assert!(!value.hit(Vec2::new(7.0, 7.0), 0.0));
+
+        let value = filled(EditorValues::Hexagon {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.0,
+            vertical: true,
+        });
+        assert!(value.hit(Vec2::new(0.0, 9.5), 0.0));
+        assert!(!value.hit(Vec2::new(9.5, 0.0), 0.0));
+    }
+
+    #[test]
+    fn rectangle_corner_drag_keeps_opposite_corner() {
+        let mut value = EditorValues::Rectangle {
+            point: Vec2::new(10.0, 10.0),
+            width: 20.0,
+            height: 10.0,
+            rotation: 0.0,
+        };
+        // Dragging the bottom-right corner past the top-left one flips the box.
+        value.set_point(2, Vec2::new(0.0, 5.0));
+        assert_eq!(
+            value,
+            EditorValues::Rectangle {
+                point: Vec2::new(0.0, 5.0),
+                width: 10.0,
+                height: 5.0,
+                rotation: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn triangle_points_move_independently() {
+        let mut value = EditorValues::Triangle {
+            point_a: Vec2::new(0.0, 0.0),
+            point_b: Vec2::new(10.0, 0.0),
+            point_c: Vec2::new(0.0, 10.0),
+        };
+        value.set_point(2, Vec2::new(5.0, 20.0));
+        assert_eq!(
+            value.points(),
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(5.0, 20.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn concave_polygon_is_ear_clipped() {
+        // An L shape with its reflex corner at (10, 10).
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(0.0, 20.0),
+        ];
+        let triangles = EditorValues::triangulate(&points);
+        assert_eq!(triangles.len(), points.len() - 2);
+
+        let area: f32 = triangles
+            .iter()
+            .map(|[a, b, c]| (*b - *a).perp_dot(*c - *a).abs() / 2.0)
+            .sum();
+        assert!((area - 300.0).abs() < 0.01);
+
+        let value = filled(EditorValues::Polygon { points });
+        assert!(value.hit(Vec2::new(5.0, 15.0), 0.0));
+        assert!(value.hit(Vec2::new(15.0, 5.0), 0.0));
+        assert!(!value.hit(Vec2::new(15.0, 15.0), 0.0));
+    }
+
+    #[test]
+    fn polyline_hits_each_segment() {
+        let value = EditorValues::Polyline {
+            points: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+            ],
+        };
+        let value = stroked(value, 2.0);
+        assert!(value.hit(Vec2::new(5.0, 2.0), 1.0));
+        assert!(value.hit(Vec2::new(12.0, 5.0), 1.0));
+        assert!(!value.hit(Vec2::new(5.0, 5.0), 1.0));
+    }
+
+    #[test]
+    fn arc_and_pie_follow_the_sweep() {
+        // Quarter turn from the +x axis towards +y, negative sweeps run back.
+        let value = EditorValues::Arc {
+            center: Vec2::new(0.0, 0.0),
+            radius: 11.0,
+            start: 90.0,
+            sweep: -90.0,
+        };
+        let value = stroked(value, 2.0);
+        assert!(value.hit(Vec2::new(7.8, 7.8), 0.0));
+        assert!(!value.hit(Vec2::new(5.0, 5.0), 0.0));
+        assert!(!value.hit(Vec2::new(-7.8, 7.8), 0.0));
+        let bounds = value.value.bounds();
+        assert!((bounds.x - 0.0).abs() < 0.01 && (bounds.y - 0.0).abs() < 0.01);
+        assert!((bounds.w - 11.0).abs() < 0.01 && (bounds.h - 11.0).abs() < 0.01);
+
+        let value = filled(EditorValues::Pie {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.0,
+            start: 0.0,
+            sweep: 270.0,
+        });
+        assert!(value.hit(Vec2::new(-5.0, -1.0), 0.0));
+        assert!(!value.hit(Vec2::new(5.0, -1.0), 0.0));
+        let bounds = value.bounds();
+        assert!((bounds.x + 10.0).abs() < 0.01 && (bounds.y + 10.0).abs() < 0.01);
+        assert!((bounds.w - 20.0).abs() < 0.01 && (bounds.h - 20.0).abs() < 0.01);
+
+        let area: f32 = EditorValues::slices(Vec2::ZERO, 10.0, 0.0, 270.0)
+            .iter()
+            .map(|[a, b, c]| (*b - *a).perp_dot(*c - *a).abs() / 2.0)
+            .sum();
+        let exact = 75.0 * std::f32::consts::PI;
+        assert!(area < exact && area > exact * 0.9);
+    }
+
+    #[test]
+    fn star_hits_between_its_points() {
+        let value = filled(EditorValues::Poly {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.0,
+            sides: 4,
+            rotation: 0.0,
+            star: Some(0.5),
+        });
+        let corners = value.value.corners();
+        assert_eq!(corners.len(), 8);
+        assert!(corners[0].distance(Vec2::new(10.0, 0.0)) < 0.01);
+        assert!((corners[1].length() - 5.0).abs() < 0.01);
+        assert!(value.hit(Vec2::new(9.0, 0.0), 0.0));
+        // The notch between two points is left empty.
+        assert!(!value.hit(Vec2::new(4.0, 4.0), 0.0));
+
+        let value = EditorValues::Poly {
+            center: Vec2::new(0.0, 0.0),
+            radius: 11.0,
+            sides: 4,
+            rotation: 0.0,
+            star: None,
+        };
+        let value = stroked(value, 2.0);
+        assert!(value.hit(Vec2::new(11.0, 0.0), 0.0));
+        assert!(!value.hit(Vec2::new(9.0, 0.0), 0.0));
+        assert!((value.bounds().w - 24.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn open_shapes_are_only_stroked() {
+        let value = EditorValues::Line {
+            point_a: Vec2::new(0.0, 0.0),
+            point_b: Vec2::new(10.0, 0.0),
+        };
+        let element = EditorElement::new(value, Some(RED), Some(RED), 2.0);
+        assert_eq!(element.fill, None);
+
+        // A stroked circle leaves its inside to the shapes below.
+        let value = EditorValues::Circle {
+            center: Vec2::new(0.0, 0.0),
+            radius: 10.0,
+        };
+        let element = stroked(value.clone(), 2.0);
+        assert!(!element.hit(Vec2::new(0.0, 0.0), 0.0));
+        let element = EditorElement::new(value, Some(RED), Some(RED), 2.0);
+        assert!(element.hit(Vec2::new(0.0, 0.0), 0.0));
+        assert!(element.hit(Vec2::new(10.9, 0.0), 0.0));
+        assert!((element.bounds().w - 22.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn pieces_cover_the_same_positions() {
+        let values = [
+            EditorValues::Poly {
+                center: Vec2::new(0.0, 0.0),
+                radius: 10.0,
+                sides: 5,
+                rotation: 0.0,
+                star: Some(0.5),
+            },
+            EditorValues::Hexagon {
+                center: Vec2::new(0.0, 0.0),
+                radius: 10.0,
+                vertical: true,
+            },
+            EditorValues::Polyline {
+                points: vec![
+                    Vec2::new(-8.0, -8.0),
+                    Vec2::new(8.0, 0.0),
+                    Vec2::new(-8.0, 8.0),
+                ],
+            },
+        ];
+        for value in values {
+            let (fills, strokes) = (value.fills(), value.strokes());
+            for y in -12..=12 {
+                for x in -12..=12 {
+                    let position = Vec2::new(x as f32 + 0.25, y as f32 + 0.25);
+                    let inside = fills.iter().any(|i| i.contains(position));
+                    assert_eq!(inside, value.contains(position));
+                    let stroke = strokes
+                        .iter()
+                        .map(|i| i.stroke(position, 2.0))
+                        .fold(f32::INFINITY, f32::min);
+                    assert_eq!(stroke, value.stroke(position, 2.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_hexagons_turn_their_corners() {
+        let center = Vec2::new(0.0, 0.0);
+        let mut value = EditorValues::Hexagon {
+            center,
+            radius: 10.0,
+            vertical: false,
+        };
+        value.rotate(Vec2::new(10.0, 0.0), FRAC_PI_2 / 2.0);
+        let corners = value.corners();
+        assert!(matches!(value, EditorValues::Poly { sides: 6, .. }));
+        let pivot = Vec2::new(10.0, 0.0);
+        let expected = EditorValues::Hexagon {
+            center,
+            radius: 10.0,
+            vertical: false,
+        }
+        .corners()
+        .iter()
+        .map(|i| pivot + EditorValues::local(*i - pivot, -FRAC_PI_2 / 2.0))
+        .collect::<Vec<_>>();
+        for (a, b) in corners.iter().zip(&expected) {
+            assert!(a.distance(*b) < 0.001);
+        }
+
+        // A quarter turn is still a hexagon, standing on a corner.
+        let mut value = EditorValues::Hexagon {
+            center,
+            radius: 10.0,
+            vertical: false,
+        };
+        value.rotate(center, FRAC_PI_2);
+        assert_eq!(
+            value,
+            EditorValues::Hexagon {
+                center,
+                radius: 10.0,
+                vertical: true,
+            }
+        );
+    }
 }
@@ -1,43 +1,65 @@
+use macroquad::prelude::draw_circle;
 use macroquad::prelude::draw_circle_lines;
 use macroquad::prelude::draw_line;
+use macroquad::prelude::draw_rectangle;
+use macroquad::prelude::draw_rectangle_lines;
+use macroquad::prelude::is_key_down;
+use macroquad::prelude::is_key_pressed;
 use macroquad::prelude::is_mouse_button_pressed;
 use macroquad::prelude::is_mouse_button_released;
-use macroquad::prelude::screen_height;
-use macroquad::prelude::screen_width;
+use macroquad::prelude::mouse_wheel;
+use macroquad::prelude::KeyCode;
 use macroquad::prelude::MouseButton;
 use macroquad::prelude::Vec2;
 use macroquad::prelude::DARKGRAY;
+use macroquad::prelude::GREEN;
+use macroquad::prelude::SKYBLUE;
 use macroquad::prelude::YELLOW;
+use serde::Deserialize;
+use serde::Serialize;
 
 // use crate::studio::EditorShapes;
 const SIZE_RESTRICTION: f32 = 10.0;
 
+use super::EditorBounds;
+use super::EditorButton;
 use super::EditorButtons;
 use super::EditorElement;
+use super::EditorLayer;
+use super::EditorMarquees;
+use super::EditorPicker;
+use super::EditorSegments;
 use super::EditorState;
 use super::EditorValues;
+use super::POLY_SIDES_MAX;
+use super::POLY_SIDES_MIN;
+use super::POLY_STAR;
 use super::SIZE_POINT;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EditorElements {
-    // Arc,
+    Arc,
+    Pie,
     Line,
-    // Poly,
-    // PolyLine,
+    // Outline tools saved by older documents open as the shape tools.
+    #[serde(alias = "PolyLine")]
+    Poly,
+    #[serde(alias = "CircleLine")]
     Circle,
-    CircleLine,
+    #[serde(alias = "EllipseLine")]
     Ellipse,
-    EllipseLine,
     Rectangle,
     // RectangleLine,
     Triangle,
     Hexagon,
+    Polyline,
+    Polygon,
+    Path,
 }
 
 impl EditorElements {
     fn element(state: &mut EditorState, current: Vec2, position: Vec2) -> EditorElement {
         let element = state.element;
-        let element_color = state.element_color;
         let element_value = match element {
             EditorElements::Hexagon => {
                 let radius = current.distance(position);
@@ -49,15 +71,26 @@ impl EditorElements {
                     vertical,
                 }
             }
-            EditorElements::Circle => {
-                let radius = current.distance(position);
+            EditorElements::Poly => {
+                // The drag points at the first corner.
+                let offset = position - current;
                 let center = current;
-                EditorValues::Circle { center, radius }
+                let radius = offset.length();
+                let sides = state.element_sides;
+                let rotation = offset.y.atan2(offset.x).to_degrees();
+                let star = state.element_star;
+                EditorValues::Poly {
+                    center,
+                    radius,
+                    sides,
+                    rotation,
+                    star,
+                }
             }
-            EditorElements::CircleLine => {
+            EditorElements::Circle => {
                 let radius = current.distance(position);
                 let center = current;
-                EditorValues::CircleLine { center, radius }
+                EditorValues::Circle { center, radius }
             }
             EditorElements::Ellipse => {
                 let width = (position.x - current.x).abs();
@@ -71,27 +104,10 @@ impl EditorElements {
                     rotation,
                 }
             }
-            EditorElements::EllipseLine => {
-                let width = (position.x - current.x).abs();
-                let height = (position.y - current.y).abs();
-                let rotation = 0.0;
-                let center = current;
-                EditorValues::EllipseLine {
-                    center,
-                    height,
-                    width,
-                    rotation,
-                }
-            }
             EditorElements::Line => {
                 let point_a = current;
                 let point_b = position;
-                let thickness = state.element_thickness;
-                EditorValues::Line {
-                    point_a,
-                    point_b,
-                    thickness,
-                }
+                EditorValues::Line { point_a, point_b }
             }
             EditorElements::Rectangle => {
                 let x = current.x.min(position.x);
@@ -107,103 +123,424 @@ impl EditorElements {
                     rotation,
                 }
             }
+            EditorElements::Arc | EditorElements::Pie => {
+                // The first drag sets the radius and start, previewed as a full turn.
+                let (center, edge, sweep) = match state.points[..] {
+                    [center, edge] => (center, edge, state.sweep),
+                    _ => (current, position, 360.0),
+                };
+                let offset = edge - center;
+                let radius = offset.length();
+                let start = offset.y.atan2(offset.x).to_degrees();
+                if element == EditorElements::Arc {
+                    EditorValues::Arc {
+                        center,
+                        radius,
+                        start,
+                        sweep,
+                    }
+                } else {
+                    EditorValues::Pie {
+                        center,
+                        radius,
+                        start,
+                        sweep,
+                    }
+                }
+            }
             EditorElements::Triangle => {
-                let point_a = current;
-                let point_b = position;
-                let point_c = Vec2::new(current.x, current.y * 0.5);
+                let point_a = state.points.first().copied().unwrap_or(current);
+                let point_b = state.points.get(1).copied().unwrap_or(position);
+                let point_c = position;
                 EditorValues::Triangle {
                     point_a,
                     point_b,
                     point_c,
                 }
             }
+            EditorElements::Polyline | EditorElements::Polygon => {
+                // The cursor previews the next point until the shape is finished.
+                let mut points = state.points.clone();
+                if points.is_empty() {
+                    points.push(current);
+                }
+                points.push(position);
+                if element == EditorElements::Polyline {
+                    EditorValues::Polyline { points }
+                } else {
+                    EditorValues::Polygon { points }
+                }
+            }
+            EditorElements::Path => {
+                let restriction = SIZE_RESTRICTION * state.camera.unit();
+                let start = state.points.first().copied().unwrap_or(current);
+                let mut segments = state.segments.clone();
+                match state.current.filter(|_| !state.points.is_empty()) {
+                    // Dragging from a new anchor pulls out its handles.
+                    Some(anchor) => {
+                        let into = (anchor.distance(position) > restriction)
+                            .then_some(anchor * 2.0 - position);
+                        segments.push(EditorSegments::new(state.tangent, into, anchor));
+                    }
+                    None => segments.push(EditorSegments::new(state.tangent, None, position)),
+                }
+                let closed = false;
+                EditorValues::Path {
+                    start,
+                    segments,
+                    closed,
+                }
+            }
         };
-        EditorElement::new(element_value, element_color)
+        state.style(element_value)
+    }
+
+    // Whether the tool is placed point by point instead of with one drag.
+    fn clicks(&self) -> bool {
+        matches!(
+            self,
+            EditorElements::Triangle
+                | EditorElements::Arc
+                | EditorElements::Pie
+                | EditorElements::Polyline
+                | EditorElements::Polygon
+                | EditorElements::Path
+        )
+    }
+
+    // Adds one point per click, a drag places the first two at once. Triangles
+    // finish on the third point, polylines and polygons with a second click on
+    // the last point or a click on the first one.
+    fn click(state: &mut EditorState, current: Vec2, position: Vec2) {
+        let restriction = SIZE_RESTRICTION * state.camera.unit();
+        if state.points.is_empty() {
+            state.points.push(current);
+        }
+        let first = state.points[0];
+        let last = state.points[state.points.len() - 1];
+        if state.element != EditorElements::Triangle {
+            if state.points.len() > 2 && first.distance(position) <= restriction {
+                if state.element == EditorElements::Polyline {
+                    state.points.push(first);
+                }
+                EditorElements::finish(state);
+                return;
+            }
+            if state.points.len() > 1 && last.distance(position) <= restriction {
+                EditorElements::finish(state);
+                return;
+            }
+        }
+        if last.distance(position) > restriction {
+            state.points.push(position);
+        }
+        if state.element == EditorElements::Triangle && state.points.len() == 3 {
+            let element = EditorElements::element(state, current, position);
+            state.points.clear();
+            state.save();
+            state.add(element);
+        }
+    }
+
+    // Arcs take the radius and start from a drag, then follow the cursor for
+    // the sweep until the next click.
+    fn arc(state: &mut EditorState, current: Vec2, position: Vec2) {
+        if state.points.is_empty() {
+            if current.distance(position) > SIZE_RESTRICTION * state.camera.unit() {
+                state.points = vec![current, position];
+                state.sweep = 0.0;
+            }
+            return;
+        }
+        let element = EditorElements::element(state, current, position);
+        state.points.clear();
+        if state.sweep.abs() > f32::EPSILON {
+            state.save();
+            state.add(element);
+        }
+    }
+
+    // Side count on the wheel while dragging, star mode on S and the star
+    // ratio on SHIFT and the wheel. The wheel zooms otherwise.
+    fn poly(state: &mut EditorState) {
+        if is_key_pressed(KeyCode::S) && !is_key_down(KeyCode::LeftSuper) {
+            state.element_star = match state.element_star {
+                Some(_) => None,
+                None => Some(POLY_STAR),
+            };
+        }
+        let (_, wheel) = mouse_wheel();
+        if wheel == 0.0 || state.current.is_none() {
+            return;
+        }
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        match state.element_star {
+            Some(star) if shift => {
+                state.element_star = Some((star + wheel.signum() * 0.05).clamp(0.05, 1.0));
+            }
+            _ => {
+                let sides = state.element_sides as f32 + wheel.signum();
+                state.element_sides =
+                    sides.clamp(POLY_SIDES_MIN as f32, POLY_SIDES_MAX as f32) as u8;
+            }
+        }
+    }
+
+    // Paths add an anchor per click, dragging from it pulls out smooth handles.
+    // A click on the start closes the path, a second click on the last anchor
+    // leaves it open.
+    fn pen(state: &mut EditorState, current: Vec2, position: Vec2) {
+        let restriction = SIZE_RESTRICTION * state.camera.unit();
+        let handle = (current.distance(position) > restriction).then_some(position);
+        let Some(start) = state.points.first().copied() else {
+            state.points.push(current);
+            state.tangent = handle;
+            return;
+        };
+        let last = state.segments.last().map(|i| i.point()).unwrap_or(start);
+        if !state.segments.is_empty() {
+            if handle.is_none() && current.distance(last) <= restriction {
+                EditorElements::finish(state);
+                return;
+            }
+            if current.distance(start) <= restriction {
+                let into = handle.map(|i| start * 2.0 - i);
+                let segment = EditorSegments::new(state.tangent, into, start);
+                state.segments.push(segment);
+                EditorElements::finish(state);
+                return;
+            }
+        }
+        // The handle into the anchor mirrors the one pulled out of it.
+        let into = handle.map(|i| current * 2.0 - i);
+        let segment = EditorSegments::new(state.tangent, into, current);
+        state.segments.push(segment);
+        state.tangent = handle;
+    }
+
+    // Turns the clicked points into a polyline, polygon or path once there
+    // are enough.
+    fn finish(state: &mut EditorState) {
+        let points = state.points.clone();
+        let value = match state.element {
+            EditorElements::Path if !state.segments.is_empty() => {
+                let start = points[0];
+                let segments = std::mem::take(&mut state.segments);
+                // Closing clicks end the last segment on the start again.
+                let closed = segments.last().is_some_and(|i| i.point() == start);
+                state.tangent = None;
+                EditorValues::Path {
+                    start,
+                    segments,
+                    closed,
+                }
+            }
+            EditorElements::Polyline if points.len() > 1 => EditorValues::Polyline { points },
+            EditorElements::Polygon if points.len() > 2 => EditorValues::Polygon { points },
+            _ => return,
+        };
+        state.points.clear();
+        state.save();
+        let element = state.style(value);
+        state.add(element);
+    }
+
+    // Control point of a selected element under `position`.
+    fn vertex(state: &EditorState, position: Vec2) -> Option<(usize, usize)> {
+        let unit = state.camera.unit();
+        state.selection.iter().rev().find_map(|i| {
+            let element = state.stack.get(*i)?;
+            element
+                .value
+                .points()
+                .iter()
+                .position(|p| position.distance(*p) <= SIZE_POINT * 2.0 * unit)
+                .map(|p| (*i, p))
+        })
     }
 
     pub fn draw(state: &mut EditorState) {
-        let width = screen_width();
-        let height = screen_height();
         let position = state.position();
+        // Overlays keep their size on screen at any zoom.
+        let unit = state.camera.unit();
+        let view = state.camera.view();
 
         if state.draw && !state.drag {
-            if let Some(current) = state.current {
+            if let [center, edge] = state.points[..] {
+                // The sweep follows the cursor around, so it can pass a half turn.
+                let angle = |i: Vec2| (i - center).y.atan2((i - center).x).to_degrees();
+                let previous = angle(edge) + state.sweep;
+                let delta = (angle(position) - previous + 180.0).rem_euclid(360.0) - 180.0;
+                state.sweep = (state.sweep + delta).clamp(-360.0, 360.0);
+            }
+            if let Some(current) = state.current.or(state.points.first().copied()) {
                 let element = EditorElements::element(state, current, position);
                 let element_color = DARKGRAY;
                 element.draw(Some(element_color));
             }
-        }
-        if !state.draw && state.drag {
-            if let Some(element) = state.stack.iter_mut().find(|i| match i.value {
-                EditorValues::Circle { center, radius } => {
-                    return position.distance(center) <= radius;
+            if state.element == EditorElements::Path {
+                let last = state.segments.last().map(|i| i.point());
+                if let (Some(last), Some(tangent)) =
+                    (last.or(state.points.first().copied()), state.tangent)
+                {
+                    draw_line(last.x, last.y, tangent.x, tangent.y, unit, DARKGRAY);
+                    draw_circle(tangent.x, tangent.y, SIZE_POINT * unit, DARKGRAY);
                 }
-                EditorValues::Hexagon { center, radius, .. } => {
-                    return position.distance(center) <= radius;
+                if let Some(anchor) = state.current {
+                    let mirror = anchor * 2.0 - position;
+                    draw_line(mirror.x, mirror.y, position.x, position.y, unit, DARKGRAY);
+                    draw_circle(position.x, position.y, SIZE_POINT * unit, DARKGRAY);
+                    draw_circle(mirror.x, mirror.y, SIZE_POINT * unit, DARKGRAY);
                 }
-                EditorValues::Rectangle {
-                    point,
-                    width,
-                    height,
-                    rotation,
-                } => {
-                    let p = position - point;
-                    let rotation_sin = rotation.sin();
-                    let rotation_cos = rotation.cos();
-
-                    let x = p.x * rotation_cos + p.y * rotation_sin;
-                    let y = -p.x * rotation_sin + p.y * rotation_cos;
-
-                    return x >= -width / 2.0
-                        && x <= width / 2.0
-                        && y >= -height / 2.0
-                        && y <= height / 2.0;
+            } else if let [center, edge] = state.points[..] {
+                draw_line(center.x, center.y, edge.x, edge.y, unit, DARKGRAY);
+                draw_line(center.x, center.y, position.x, position.y, unit, DARKGRAY);
+            } else {
+                let mut points = state.points.clone();
+                points.push(position);
+                for i in points.windows(2) {
+                    draw_line(i[0].x, i[0].y, i[1].x, i[1].y, unit, DARKGRAY);
                 }
-                _ => false,
-            }) {
-                match element.value {
-                    EditorValues::Circle { ref mut center, .. } => {
-                        *center = position - state.drag_offset.unwrap_or(position);
-                    }
-                    EditorValues::Hexagon { ref mut center, .. } => {
-                        *center = position - state.drag_offset.unwrap_or(position);
+            }
+            if let Some(first) = state.points.first() {
+                let count = state.points.len() + state.segments.len();
+                if count > 2 && first.distance(position) <= SIZE_RESTRICTION * unit {
+                    draw_circle_lines(first.x, first.y, SIZE_POINT * 2.0 * unit, unit, YELLOW);
+                }
+            }
+        }
+        if let (Some((index, point)), Some(stack)) = (state.vertex, &state.drag_stack) {
+            if let (Some(element), Some(original)) = (state.stack.get_mut(index), stack.get(index))
+            {
+                element.value = original.value.clone();
+                element.value.set_point(point, position);
+            }
+        }
+        if !state.draw {
+            if let (Some((handle, bounds)), Some(stack)) = (state.handle, &state.drag_stack) {
+                let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                for i in state.selection.iter() {
+                    if let (Some(element), Some(original)) =
+                        (state.stack.get_mut(*i), stack.get(*i))
+                    {
+                        element.value = original.value.clone();
+                        handle.apply(&bounds, position, shift, &mut element.value);
                     }
-                    EditorValues::Rectangle { ref mut point, .. } => {
-                        *point = position - state.drag_offset.unwrap_or(position);
+                }
+            }
+        }
+        if !state.draw && state.drag {
+            if let Some(last) = state.drag_offset {
+                let offset = position - last;
+                for i in state.selection.iter() {
+                    if let Some(element) = state.stack.get_mut(*i) {
+                        element.value.translate(offset);
                     }
-                    _ => {}
                 }
+                state.drag_offset = Some(position);
             }
         }
 
-        for element in state.stack.iter() {
+        for element in state.stack.iter().filter(|i| state.visible(i)) {
             element.draw(None);
         }
 
+        if let Some(start) = state.marquee {
+            let marquee = EditorMarquees::rect(start, position);
+            let color = match state.marquee_mode {
+                EditorMarquees::Inside => SKYBLUE,
+                EditorMarquees::Touching => GREEN,
+            };
+            draw_rectangle(
+                marquee.x,
+                marquee.y,
+                marquee.w,
+                marquee.h,
+                color.with_alpha(0.1),
+            );
+            draw_rectangle_lines(
+                marquee.x,
+                marquee.y,
+                marquee.w,
+                marquee.h,
+                unit,
+                color.with_alpha(0.6),
+            );
+            state.camera.text(
+                state.marquee_mode.text(),
+                marquee.x,
+                marquee.y - 5.0 * unit,
+                14.0,
+                color.with_alpha(0.6),
+            );
+        }
+
+        if state.selection.len() > 1 {
+            for i in state.selection.iter() {
+                if let Some(element) = state.stack.get(*i) {
+                    let bounds = element.bounds();
+                    let color = SKYBLUE.with_alpha(0.4);
+                    draw_rectangle_lines(bounds.x, bounds.y, bounds.w, bounds.h, unit, color);
+                }
+            }
+        }
+        if !state.draw && state.direct {
+            for i in state.selection.iter() {
+                if let Some(element) = state.stack.get(*i) {
+                    if let EditorValues::Path {
+                        start,
+                        ref segments,
+                        ..
+                    } = element.value
+                    {
+                        for (a, b) in EditorSegments::handles(start, segments) {
+                            draw_line(a.x, a.y, b.x, b.y, unit, SKYBLUE.with_alpha(0.6));
+                        }
+                    }
+                    for point in element.value.points() {
+                        draw_circle(point.x, point.y, SIZE_POINT * unit, SKYBLUE.with_alpha(0.8));
+                        if position.distance(point) <= SIZE_POINT * 2.0 * unit {
+                            draw_circle_lines(
+                                point.x,
+                                point.y,
+                                SIZE_POINT * 2.0 * unit,
+                                unit,
+                                SKYBLUE,
+                            );
+                        }
+                    }
+                }
+            }
+        } else if !state.draw {
+            if let Some(bounds) = EditorBounds::new(state) {
+                bounds.draw(unit);
+            }
+        }
+
         let color = YELLOW.with_alpha(0.2);
-        for element in state.stack.iter() {
+        for element in state.stack.iter().filter(|i| state.visible(i)) {
             match element.value {
                 EditorValues::Line {
                     point_a, point_b, ..
                 } => {
-                    if position.distance(point_a) <= SIZE_POINT
-                        || position.distance(point_b) <= SIZE_POINT
+                    if position.distance(point_a) <= SIZE_POINT * unit
+                        || position.distance(point_b) <= SIZE_POINT * unit
                     {
-                        draw_circle_lines(point_a.x, point_a.y, SIZE_POINT, 1.0, color);
-                        draw_circle_lines(point_b.x, point_b.y, SIZE_POINT, 1.0, color);
+                        draw_circle_lines(point_a.x, point_a.y, SIZE_POINT * unit, unit, color);
+                        draw_circle_lines(point_b.x, point_b.y, SIZE_POINT * unit, unit, color);
                     }
                     if state.snap {
-                        if (position.x - point_a.x).abs() < SIZE_POINT {
-                            draw_line(point_a.x, 0.0, point_a.x, height, 1.0, color);
+                        if (position.x - point_a.x).abs() < SIZE_POINT * unit {
+                            draw_line(point_a.x, view.y, point_a.x, view.bottom(), unit, color);
                         }
-                        if (position.y - point_a.y).abs() < SIZE_POINT {
-                            draw_line(0.0, point_a.y, width, point_a.y, 1.0, color);
+                        if (position.y - point_a.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, point_a.y, view.right(), point_a.y, unit, color);
                         }
-                        if (position.x - point_b.x).abs() < SIZE_POINT {
-                            draw_line(point_b.x, 0.0, point_b.x, height, 1.0, color);
+                        if (position.x - point_b.x).abs() < SIZE_POINT * unit {
+                            draw_line(point_b.x, view.y, point_b.x, view.bottom(), unit, color);
                         }
-                        if (position.y - point_b.y).abs() < SIZE_POINT {
-                            draw_line(0.0, point_b.y, width, point_b.y, 1.0, color);
+                        if (position.y - point_b.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, point_b.y, view.right(), point_b.y, unit, color);
                         }
                     }
                 }
@@ -212,87 +549,67 @@ impl EditorElements {
                     let point2 = Vec2::new(center.x, center.y - radius);
                     let point3 = Vec2::new(center.x + radius, center.y);
                     let point4 = Vec2::new(center.x - radius, center.y);
-                    if position.distance(center) <= SIZE_POINT
-                        || position.distance(point1) <= SIZE_POINT
-                        || position.distance(point2) <= SIZE_POINT
-                        || position.distance(point3) <= SIZE_POINT
-                        || position.distance(point4) <= SIZE_POINT
+                    if position.distance(center) <= SIZE_POINT * unit
+                        || position.distance(point1) <= SIZE_POINT * unit
+                        || position.distance(point2) <= SIZE_POINT * unit
+                        || position.distance(point3) <= SIZE_POINT * unit
+                        || position.distance(point4) <= SIZE_POINT * unit
                     {
-                        draw_circle_lines(center.x, center.y, SIZE_POINT, 1.0, color);
+                        draw_circle_lines(center.x, center.y, SIZE_POINT * unit, unit, color);
                     }
 
                     if state.snap {
-                        if (position.x - center.x).abs() < SIZE_POINT {
-                            draw_line(center.x, 0.0, center.x, height, 1.0, color);
+                        if (position.x - center.x).abs() < SIZE_POINT * unit {
+                            draw_line(center.x, view.y, center.x, view.bottom(), unit, color);
                         }
-                        if (position.y - center.y).abs() < SIZE_POINT {
-                            draw_line(0.0, center.y, width, center.y, 1.0, color);
+                        if (position.y - center.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, center.y, view.right(), center.y, unit, color);
                         }
-                        if (position.y - point1.y).abs() < SIZE_POINT {
-                            draw_line(0.0, point1.y, width, point1.y, 1.0, color);
+                        if (position.y - point1.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, point1.y, view.right(), point1.y, unit, color);
                         }
-                        if (position.y - point2.y).abs() < SIZE_POINT {
-                            draw_line(0.0, point2.y, width, point2.y, 1.0, color);
+                        if (position.y - point2.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, point2.y, view.right(), point2.y, unit, color);
                         }
-                        if (position.x - point3.x).abs() < SIZE_POINT {
-                            draw_line(point3.x, 0.0, point3.x, height, 1.0, color);
+                        if (position.x - point3.x).abs() < SIZE_POINT * unit {
+                            draw_line(point3.x, view.y, point3.x, view.bottom(), unit, color);
                         }
-                        if (position.x - point4.x).abs() < SIZE_POINT {
-                            draw_line(point4.x, 0.0, point4.x, height, 1.0, color);
+                        if (position.x - point4.x).abs() < SIZE_POINT * unit {
+                            draw_line(point4.x, view.y, point4.x, view.bottom(), unit, color);
                         }
                     }
                 }
-                EditorValues::Rectangle {
-                    width,
-                    height,
-                    rotation,
-                    point,
-                } => {
-                    let hw = width / 2.0;
-                    let hh = height / 2.0;
-
-                    let mut corners = [
-                        Vec2::new(-hw, -hh),
-                        Vec2::new(hw, -hh),
-                        Vec2::new(hw, hh),
-                        Vec2::new(-hw, hh),
-                    ];
-
-                    for corner in &mut corners {
-                        *corner = Vec2::new(
-                            corner.x * rotation.cos() - corner.y * rotation.sin(),
-                            corner.x * rotation.sin() + corner.y * rotation.cos(),
-                        ) + point;
-                    }
+                EditorValues::Rectangle { point, .. } => {
+                    let corners = element.value.corners();
 
                     let mut highlight = false;
                     for corner in &corners {
-                        if position.distance(*corner) <= SIZE_POINT {
+                        if position.distance(*corner) <= SIZE_POINT * unit {
                             highlight = true;
                             break;
                         }
                     }
-                    if position.distance(point) <= SIZE_POINT {
+                    if position.distance(point) <= SIZE_POINT * unit {
                         highlight = true;
                     }
 
                     if highlight {
-                        draw_circle_lines(point.x, point.y, SIZE_POINT, 1.0, color);
+                        draw_circle_lines(point.x, point.y, SIZE_POINT * unit, unit, color);
                     }
 
                     if state.snap {
-                        if (position.x - point.x).abs() < SIZE_POINT {
-                            draw_line(point.x, 0.0, point.x, height, 1.0, color);
+                        if (position.x - point.x).abs() < SIZE_POINT * unit {
+                            draw_line(point.x, view.y, point.x, view.bottom(), unit, color);
                         }
-                        if (position.y - point.y).abs() < SIZE_POINT {
-                            draw_line(0.0, point.y, width, point.y, 1.0, color);
+                        if (position.y - point.y).abs() < SIZE_POINT * unit {
+                            draw_line(view.x, point.y, view.right(), point.y, unit, color);
                         }
                         for corner in &corners {
-                            if (position.x - corner.x).abs() < SIZE_POINT {
-                                draw_line(corner.x, 0.0, corner.x, height, 1.0, color);
+                            if (position.x - corner.x).abs() < SIZE_POINT * unit {
+                                draw_line(corner.x, view.y, corner.x, view.bottom(), unit, color);
                             }
-                            if (position.y - corner.y).abs() < SIZE_POINT {
-                                draw_line(0.0, corner.y, width, corner.y, 1.0, color);
+                            if (position.y - corner.y).abs() < SIZE_POINT * unit {
+                                draw_line(view.x, corner.y, view.right(), corner.y, unit, color);
                             }
                         }
                     }
@@ -302,70 +619,114 @@ impl EditorElements {
         }
     }
     pub fn actions(state: &mut EditorState) {
+        // The pointer pans the canvas while SPACE is held.
+        if state.prompt.is_some() || state.camera.pan.is_some() || is_key_down(KeyCode::Space) {
+            return;
+        }
         let position = state.position();
+        let unit = state.camera.unit();
+
+        if !state.draw || !state.element.clicks() {
+            state.points.clear();
+        }
+        if state.points.is_empty() {
+            state.segments.clear();
+            state.tangent = None;
+        }
+        if is_key_pressed(KeyCode::Enter) && state.draw {
+            EditorElements::finish(state);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            state.points.clear();
+            state.current = None;
+        }
+        if state.draw && state.element == EditorElements::Poly {
+            EditorElements::poly(state);
+        }
 
-        if is_mouse_button_pressed(MouseButton::Left) && state.draw {
+        if is_mouse_button_pressed(MouseButton::Left)
+            && state.draw
+            && EditorButton::find().is_none()
+            && !EditorPicker::blocks(state)
+            && !EditorLayer::blocks(state)
+        {
             state.current = Some(position);
         }
 
         if is_mouse_button_released(MouseButton::Left) && state.draw {
             if let Some(current) = state.current.take() {
-                if current.distance(position) > SIZE_RESTRICTION {
+                if state.element == EditorElements::Path {
+                    EditorElements::pen(state, current, position);
+                } else if matches!(state.element, EditorElements::Arc | EditorElements::Pie) {
+                    EditorElements::arc(state, current, position);
+                } else if state.element.clicks() {
+                    EditorElements::click(state, current, position);
+                } else if current.distance(position) > SIZE_RESTRICTION * unit {
                     let element = EditorElements::element(state, current, position);
                     state.save();
-                    state.stack.push(element);
+                    state.add(element);
                 }
             }
         }
 
         if is_mouse_button_pressed(MouseButton::Left) && !state.draw {
-            if let Some(element) = state.stack.iter().find(|i| match i.value {
-                EditorValues::Circle { center, radius } => {
-                    return position.distance(center) <= radius;
-                }
-                EditorValues::Hexagon { center, radius, .. } => {
-                    return position.distance(center) <= radius;
+            if EditorButton::find().is_some()
+                || EditorPicker::blocks(state)
+                || EditorLayer::blocks(state)
+            {
+                return;
+            }
+            if state.direct {
+                if let Some(vertex) = EditorElements::vertex(state, position) {
+                    state.vertex = Some(vertex);
+                    state.drag_stack = Some(state.stack.clone());
+                    return;
                 }
-                EditorValues::Rectangle {
-                    point,
-                    width,
-                    height,
-                    rotation,
-                } => {
-                    let p = position - point;
-                    let rotation_sin = rotation.sin();
-                    let rotation_cos = rotation.cos();
-
-                    let x = p.x * rotation_cos + p.y * rotation_sin;
-                    let y = -p.x * rotation_sin + p.y * rotation_cos;
-
-                    return x >= -width / 2.0
-                        && x <= width / 2.0
-                        && y >= -height / 2.0
-                        && y <= height / 2.0;
+            } else if let Some(bounds) = EditorBounds::new(state) {
+                if let Some(handle) = bounds.find(position, unit) {
+                    state.handle = Some((handle, bounds));
+                    state.drag_stack = Some(state.stack.clone());
+                    return;
                 }
-                _ => false,
-            }) {
-                match element.value {
-                    EditorValues::Circle { center, .. } => {
-                        state.drag_offset = Some(position - center);
-                        state.drag = true;
-                    }
-                    EditorValues::Hexagon { center, .. } => {
-                        state.drag_offset = Some(position - center);
-                        state.drag = true;
+            }
+            let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            let index = state
+                .stack
+                .iter()
+                .rposition(|i| state.editable(i) && i.hit(position, SIZE_POINT * unit));
+            match index {
+                Some(index) => {
+                    let selected = state.selection.contains(&index);
+                    if shift && selected {
+                        state.selection.retain(|i| *i != index);
+                    } else if shift {
+                        state.selection.push(index);
+                    } else if !selected {
+                        state.selection = vec![index];
                     }
-                    EditorValues::Rectangle { point, .. } => {
-                        state.drag_offset = Some(position - point);
+                    if state.selection.contains(&index) {
                         state.drag = true;
+                        state.drag_offset = Some(position);
+                        state.drag_stack = Some(state.stack.clone());
                     }
-                    _ => {}
+                }
+                None => {
+                    state.marquee = Some(position);
                 }
             }
         }
 
         if is_mouse_button_released(MouseButton::Left) && !state.draw {
+            if let Some(start) = state.marquee.take() {
+                state.select(EditorMarquees::rect(start, position));
+            }
             state.drag = false;
+            state.drag_offset = None;
+            state.handle = None;
+            state.vertex = None;
+            if let Some(stack) = state.drag_stack.take() {
+                state.save_from(stack);
+            }
         }
     }
 }
@@ -373,16 +734,18 @@ impl EditorElements {
 impl From<EditorElements> for EditorButtons {
     fn from(i: EditorElements) -> Self {
         match i {
-            // EditorElements::Arc => EditorButtons::Arc,
+            EditorElements::Arc => EditorButtons::Arc,
+            EditorElements::Pie => EditorButtons::Pie,
             EditorElements::Line => EditorButtons::Line,
-            // EditorElements::Poly => EditorButtons::Poly,
+            EditorElements::Poly => EditorButtons::Poly,
             EditorElements::Circle => EditorButtons::Circle,
-            EditorElements::CircleLine => EditorButtons::CircleLine,
             EditorElements::Ellipse => EditorButtons::Ellipse,
-            EditorElements::EllipseLine => EditorButtons::EllipseLine,
             EditorElements::Rectangle => EditorButtons::Rectangle,
             EditorElements::Triangle => EditorButtons::Triangle,
             EditorElements::Hexagon => EditorButtons::Hexagon,
+            EditorElements::Polyline => EditorButtons::Polyline,
+            EditorElements::Polygon => EditorButtons::Polygon,
+            EditorElements::Path => EditorButtons::Path,
         }
     }
 }
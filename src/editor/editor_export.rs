@@ -0,0 +1,585 @@
+use macroquad::prelude::Color;
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use macroquad::prelude::YELLOW;
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::EditorArtboard;
+use super::EditorColorOption;
+use super::EditorElement;
+use super::EditorLayer;
+use super::EditorPalette;
+use super::EditorValues;
+
+pub const EXPORT_EXTENSION: &str = "rs";
+pub const EXPORT_MARKER: &str = "// unkNOWn Shape:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "EditorFramesValue")]
+pub enum EditorFrames {
+    #[default]
+    Content,
+    // Index into the document artboards.
+    Artboard(usize),
+}
+
+// Frames as written before artboards, the display frames are the first two
+// default artboards.
+#[derive(Deserialize)]
+#[serde(rename = "EditorFrames")]
+enum EditorFramesValue {
+    Content,
+    Display,
+    DisplayHd,
+    Artboard(usize),
+}
+
+impl From<EditorFramesValue> for EditorFrames {
+    fn from(value: EditorFramesValue) -> Self {
+        match value {
+            EditorFramesValue::Content => EditorFrames::Content,
+            EditorFramesValue::Display => EditorFrames::Artboard(0),
+            EditorFramesValue::DisplayHd => EditorFrames::Artboard(1),
+            EditorFramesValue::Artboard(i) => EditorFrames::Artboard(i),
+        }
+    }
+}
+
+impl EditorFrames {
+    // Missing artboards fall back to the content.
+    pub fn text(&self, artboards: &[EditorArtboard]) -> String {
+        match self {
+            EditorFrames::Artboard(i) if *i < artboards.len() => artboards[*i].name.clone(),
+            _ => "CONTENT".to_string(),
+        }
+    }
+
+    pub fn next(&self, artboards: &[EditorArtboard]) -> Self {
+        match self {
+            EditorFrames::Content if !artboards.is_empty() => EditorFrames::Artboard(0),
+            EditorFrames::Artboard(i) if i + 1 < artboards.len() => EditorFrames::Artboard(i + 1),
+            _ => EditorFrames::Content,
+        }
+    }
+
+    pub fn rect(&self, stack: &[EditorElement], artboards: &[EditorArtboard]) -> Rect {
+        match self {
+            EditorFrames::Artboard(i) if *i < artboards.len() => artboards[*i].rect(),
+            _ => stack
+                .iter()
+                .map(|i| i.bounds())
+                .reduce(|a, b| a.combine_with(b))
+                .unwrap_or_default(),
+        }
+    }
+
+    // Keeps pointing at the same artboard after the one at `index` is removed.
+    pub fn remove(&self, index: usize) -> Self {
+        match self {
+            EditorFrames::Artboard(i) if *i == index => EditorFrames::Content,
+            EditorFrames::Artboard(i) if *i > index => EditorFrames::Artboard(i - 1),
+            frame => *frame,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorExport {
+    // Name of the generated draw function.
+    pub name: String,
+    // Module path of the generated file relative to the document, e.g. `shapes::ship`.
+    pub module: String,
+    // Existing source file where the generated function is updated in place.
+    pub target: Option<PathBuf>,
+    // Area of the canvas written by the exporters, code is placed relative to
+    // its top left corner.
+    pub frame: EditorFrames,
+    // Pixels per canvas unit of PNG exports.
+    pub scale: f32,
+    // Solid PNG background, transparent when `None`.
+    #[serde(with = "EditorColorOption")]
+    pub background: Option<Color>,
+    // One function per layer instead of a single one.
+    pub split: bool,
+    // Module path of the palette constants, colors from the palette refer to
+    // them by name. Empty when they are in scope.
+    pub palette: String,
+    // Width of the bounds guide drawn around the shapes, follows the stroke
+    // width of new shapes.
+    pub guide: f32,
+}
+
+impl Default for EditorExport {
+    fn default() -> Self {
+        Self {
+            name: "draw".to_string(),
+            module: String::new(),
+            target: None,
+            frame: EditorFrames::Content,
+            scale: 1.0,
+            background: None,
+            split: false,
+            palette: "palette".to_string(),
+            guide: 1.0,
+        }
+    }
+}
+
+impl EditorExport {
+    pub fn path(&self, document: Option<&Path>) -> PathBuf {
+        match &self.target {
+            Some(target) => target.clone(),
+            None => self.file(document, EXPORT_EXTENSION),
+        }
+    }
+
+    pub fn file(&self, document: Option<&Path>, extension: &str) -> PathBuf {
+        let directory = document
+            .and_then(|i| i.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let module = self
+            .module
+            .split("::")
+            .map(str::trim)
+            .filter(|i| !i.is_empty())
+            .collect::<Vec<_>>();
+        if !module.is_empty() {
+            return directory.join(module.join("/")).with_extension(extension);
+        }
+        let name = document
+            .and_then(|i| i.file_stem())
+            .map(|i| i.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.name.clone());
+        directory.join(name).with_extension(extension)
+    }
+
+    pub fn code(
+        &self,
+        stack: &[EditorElement],
+        layers: &[EditorLayer],
+        artboards: &[EditorArtboard],
+        palette: &EditorPalette,
+    ) -> String {
+        let mut content = String::new();
+
+        // Palette colors go out as constants, others and colors whose entry
+        // is gone as literals.
+        let color = |color: Color, swatch: &Option<String>| match swatch
+            .as_deref()
+            .and_then(|i| palette.reference(i))
+        {
+            Some(constant) if self.palette.is_empty() => constant,
+            Some(constant) => format!("{}::{}", self.palette, constant),
+            None => format!("{:?}", color),
+        };
+
+        let bounds = self.frame.rect(stack, artboards);
+        let origin = bounds.point();
+
+        let body = |layer: Option<usize>| {
+            let mut content = String::new();
+            for i in stack
+                .iter()
+                .filter(|i| layer.is_none_or(|id| i.layer == id))
+            {
+                // Paths go out as the lines or triangles they are drawn with.
+                let value = i.value.flatten();
+                if let Some(fill) = i.fill {
+                    let fill = color(fill, &i.fill_swatch);
+                    content.push_str(&EditorExport::fill(&value, &fill, origin));
+                }
+                if let Some(stroke) = i.stroke {
+                    let stroke = color(stroke, &i.stroke_swatch);
+                    content.push_str(&EditorExport::stroke(&value, &stroke, i.thickness, origin));
+                }
+            }
+            content
+        };
+
+        // Split exports get a function per layer with elements, the main
+        // function calls them in order. All of them share the markers.
+        content.push_str(&format!("{} {} begin\n", EXPORT_MARKER, self.name));
+        let mut calls = String::new();
+        if self.split {
+            let functions = EditorLayer::functions(layers, &self.name);
+            for (layer, function) in layers.iter().zip(functions) {
+                if !stack.iter().any(|i| i.layer == layer.id) {
+                    continue;
+                }
+                content.push_str(&format!("pub fn {}(x: f32, y: f32) {{\n", function));
+                content.push_str(&body(Some(layer.id)));
+                content.push_str("}\n\n");
+                calls.push_str(&format!("   {}(x, y);\n", function));
+            }
+        } else {
+            calls = body(None);
+        }
+        content.push_str(&format!("pub fn {}(x: f32, y: f32) {{\n", self.name));
+        content.push_str(&format!(
+            "   draw_rectangle_lines(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {:.1}, {:?});\n",
+            0.0, 0.0, bounds.w, bounds.h, self.guide, YELLOW
+        ));
+        content.push_str(&calls);
+        content.push_str("}\n");
+        content.push_str(&format!("{} {} end\n", EXPORT_MARKER, self.name));
+        content
+    }
+
+    // Draw calls filling `value`, relative to `origin`.
+    fn fill(value: &EditorValues, color: &str, origin: Vec2) -> String {
+        let mut content = String::new();
+        let (min_x, min_y) = (origin.x, origin.y);
+        match *value {
+            EditorValues::Circle { center, radius } => content.push_str(&format!(
+                "   draw_circle(x + {:.1}, y + {:.1}, {:.1}, {});\n",
+                center.x - min_x,
+                center.y - min_y,
+                radius,
+                color,
+            )),
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                for [a, b, c] in EditorValues::slices(center, radius, start, sweep) {
+                    content.push_str(&format!(
+                        "   draw_triangle(vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), {});\n",
+                        a.x - min_x,
+                        a.y - min_y,
+                        b.x - min_x,
+                        b.y - min_y,
+                        c.x - min_x,
+                        c.y - min_y,
+                        color,
+                    ));
+                }
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => content.push_str(&format!(
+                "   draw_ellipse(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {:.1}, {});\n",
+                center.x - min_x,
+                center.y - min_y,
+                width,
+                height,
+                rotation,
+                color,
+            )),
+            EditorValues::Rectangle {
+                width,
+                height,
+                rotation,
+                point,
+            } => {
+                content.push_str(&format!(
+                    "   draw_rectangle_ex(x + {:.1}, y + {:.1}, {:.1}, {:.1}, DrawRectangleParams {{ offset: vec2(0.0, 0.0), rotation: {:?}, color: {} }});\n",
+                    point.x - min_x,
+                    point.y - min_y,
+                    width,
+                    height,
+                    rotation,
+                    color,
+                ));
+            }
+            EditorValues::Triangle {
+                point_a,
+                point_b,
+                point_c,
+            } => content.push_str(&format!(
+                "   draw_triangle(vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), {});\n",
+                point_a.x - min_x,
+                point_a.y - min_y,
+                point_b.x - min_x,
+                point_b.y - min_y,
+                point_c.x - min_x,
+                point_c.y - min_y,
+                color,
+            )),
+            EditorValues::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                content.push_str(&format!(
+                    "   draw_hexagon(x + {:.1}, y + {:.1}, {:.1}, 0.0, {:?}, {}, {});\n",
+                    center.x - min_x,
+                    center.y - min_y,
+                    radius,
+                    vertical,
+                    color,
+                    color,
+                ));
+            }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star: None,
+            } => content.push_str(&format!(
+                "   draw_poly(x + {:.1}, y + {:.1}, {}, {:.1}, {:.1}, {});\n",
+                center.x - min_x,
+                center.y - min_y,
+                sides,
+                radius,
+                rotation,
+                color,
+            )),
+            EditorValues::Poly { center, .. } => {
+                // Stars have no draw call of their own and go out as triangles.
+                for [a, b, c] in EditorValues::fan(center, &value.corners()) {
+                    content.push_str(&format!(
+                        "   draw_triangle(vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), {});\n",
+                        a.x - min_x,
+                        a.y - min_y,
+                        b.x - min_x,
+                        b.y - min_y,
+                        c.x - min_x,
+                        c.y - min_y,
+                        color,
+                    ));
+                }
+            }
+            EditorValues::Polygon { ref points } => {
+                // Polygons have no draw call of their own and go out as triangles.
+                for [a, b, c] in EditorValues::triangulate(points) {
+                    content.push_str(&format!(
+                        "   draw_triangle(vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), vec2(x + {:.1}, y + {:.1}), {});\n",
+                        a.x - min_x,
+                        a.y - min_y,
+                        b.x - min_x,
+                        b.y - min_y,
+                        c.x - min_x,
+                        c.y - min_y,
+                        color,
+                    ));
+                }
+            }
+            _ => {}
+        }
+        content
+    }
+
+    // Draw calls stroking `value` centered on its outline, like
+    // `EditorValues::draw_stroke`.
+    fn stroke(value: &EditorValues, color: &str, thickness: f32, origin: Vec2) -> String {
+        let mut content = String::new();
+        let (min_x, min_y) = (origin.x, origin.y);
+        let half = thickness / 2.0;
+        match *value {
+            EditorValues::Circle { center, radius } => content.push_str(&format!(
+                "   draw_circle_lines(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {});\n",
+                center.x - min_x,
+                center.y - min_y,
+                radius - half,
+                thickness,
+                color,
+            )),
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => {
+                let (from, span) = EditorValues::span(start, sweep);
+                content.push_str(&format!(
+                    "   draw_arc(x + {:.1}, y + {:.1}, {}, {:.1}, {:.1}, {:.1}, {:.1}, {});\n",
+                    center.x - min_x,
+                    center.y - min_y,
+                    EditorValues::sides(radius),
+                    radius - half,
+                    from,
+                    thickness,
+                    span,
+                    color,
+                ));
+            }
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => content.push_str(&format!(
+                "   draw_ellipse_lines(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {:.1}, {:.1}, {});\n",
+                center.x - min_x,
+                center.y - min_y,
+                width,
+                height,
+                rotation,
+                thickness,
+                color,
+            )),
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => {
+                let point = point + EditorValues::local(Vec2::splat(-half), -rotation);
+                content.push_str(&format!(
+                    "   draw_rectangle_lines_ex(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {:.1}, DrawRectangleParams {{ offset: vec2(0.0, 0.0), rotation: {:?}, color: {} }});\n",
+                    point.x - min_x,
+                    point.y - min_y,
+                    width + thickness,
+                    height + thickness,
+                    thickness,
+                    rotation,
+                    color,
+                ));
+            }
+            EditorValues::Hexagon {
+                center,
+                radius,
+                vertical,
+            } => {
+                let (radius, width) = EditorValues::ring(radius, 6, thickness);
+                let rotation = if vertical { 90.0 } else { 0.0 };
+                content.push_str(&format!(
+                    "   draw_poly_lines(x + {:.1}, y + {:.1}, 6, {:.1}, {:.1}, {:.1}, {});\n",
+                    center.x - min_x,
+                    center.y - min_y,
+                    radius,
+                    rotation,
+                    width,
+                    color,
+                ));
+            }
+            EditorValues::Poly {
+                center,
+                radius,
+                sides,
+                rotation,
+                star: None,
+            } => {
+                let (radius, width) = EditorValues::ring(radius, sides, thickness);
+                content.push_str(&format!(
+                    "   draw_poly_lines(x + {:.1}, y + {:.1}, {}, {:.1}, {:.1}, {:.1}, {});\n",
+                    center.x - min_x,
+                    center.y - min_y,
+                    sides,
+                    radius,
+                    rotation,
+                    width,
+                    color,
+                ));
+            }
+            _ => {
+                for i in value.outline().windows(2) {
+                    content.push_str(&format!(
+                        "   draw_line(x + {:.1}, y + {:.1}, x + {:.1}, y + {:.1}, {:.1}, {});\n",
+                        i[0].x - min_x,
+                        i[0].y - min_y,
+                        i[1].x - min_x,
+                        i[1].y - min_y,
+                        thickness,
+                        color,
+                    ));
+                }
+            }
+        }
+        content
+    }
+
+    // Replaces the generated block of the function in `content`, or appends it
+    // when the function was never exported there before.
+    // Replaces the code between the markers of this export or appends it,
+    // a marker without its pair is left for the user to fix.
+    pub fn update(&self, content: &str, code: &str) -> Result<String, String> {
+        let begin = format!("{} {} begin", EXPORT_MARKER, self.name);
+        let end = format!("{} {} end", EXPORT_MARKER, self.name);
+
+        match (content.find(&begin), content.find(&end)) {
+            (Some(start), Some(_)) => {
+                let Some(offset) = content[start..].find(&end) else {
+                    return Err(format!("`{}` comes before `{}`", end, begin));
+                };
+                let mut stop = start + offset + end.len();
+                if content[stop..].starts_with('\n') {
+                    stop += 1;
+                }
+                Ok(format!("{}{}{}", &content[..start], code, &content[stop..]))
+            }
+            (Some(_), None) => Err(format!("`{}` has no `{}`", begin, end)),
+            (None, Some(_)) => Err(format!("`{}` has no `{}`", end, begin)),
+            (None, None) => {
+                let content = content.trim_end();
+                if content.is_empty() {
+                    Ok(code.to_string())
+                } else {
+                    Ok(format!("{}\n\n{}", content, code))
+                }
+            }
+        }
+    }
+
+    pub fn write(
+        &self,
+        path: &Path,
+        stack: &[EditorElement],
+        layers: &[EditorLayer],
+        artboards: &[EditorArtboard],
+        palette: &EditorPalette,
+    ) -> Result<(), Box<dyn Error>> {
+        let code = self.code(stack, layers, artboards, palette);
+        let content = if path.exists() {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            self.update(&content, &code)
+                .map_err(|e| format!("Failed to update {}: {}", path.display(), e))?
+        } else {
+            if let Some(directory) = path.parent().filter(|i| !i.as_os_str().is_empty()) {
+                fs::create_dir_all(directory)
+                    .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+            }
+            format!("use macroquad::prelude::*;\n\n{}", code)
+        };
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EditorExport;
+    use super::EXPORT_MARKER;
+
+    #[test]
+    fn updates_code_between_its_markers() {
+        let export = EditorExport::default();
+        let code = format!("{0} draw begin\nNEW\n{0} draw end\n", EXPORT_MARKER);
+        let content = format!(
+            "use a;\n{0} draw begin\nOLD\n{0} draw end\nfn b() {{}}\n",
+            EXPORT_MARKER
+        );
+        assert_eq!(
+            export.update(&content, &code).unwrap(),
+            format!("use a;\n{}fn b() {{}}\n", code)
+        );
+        assert_eq!(
+            export.update("use a;\n", &code).unwrap(),
+            format!("use a;\n\n{}", code)
+        );
+
+        // Half of the markers leaves the file alone.
+        let begin = format!("use a;\n{} draw begin\nOLD\n", EXPORT_MARKER);
+        assert!(export
+            .update(&begin, &code)
+            .unwrap_err()
+            .contains("draw begin"));
+        let end = format!("OLD\n{} draw end\n", EXPORT_MARKER);
+        assert!(export.update(&end, &code).unwrap_err().contains("draw end"));
+    }
+}
@@ -0,0 +1,244 @@
+use macroquad::prelude::draw_circle_lines;
+use macroquad::prelude::draw_line;
+use macroquad::prelude::draw_rectangle;
+use macroquad::prelude::Vec2;
+use macroquad::prelude::SKYBLUE;
+use std::f32::consts::PI;
+
+use super::EditorState;
+use super::EditorValues;
+use super::SIZE_POINT;
+
+// Distance of the rotation knob above the top edge of the selection.
+pub const HANDLE_ROTATE: f32 = 20.0;
+// Rotation step used while SHIFT is held.
+pub const HANDLE_ROTATE_STEP: f32 = PI / 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorHandles {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+    Rotate,
+}
+
+// Oriented box around the selection, `point` is the top-left corner and
+// `rotation` is in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EditorBounds {
+    pub point: Vec2,
+    pub size: Vec2,
+    pub rotation: f32,
+}
+
+impl EditorHandles {
+    pub const ALL: [EditorHandles; 9] = [
+        EditorHandles::TopLeft,
+        EditorHandles::Top,
+        EditorHandles::TopRight,
+        EditorHandles::Right,
+        EditorHandles::BottomRight,
+        EditorHandles::Bottom,
+        EditorHandles::BottomLeft,
+        EditorHandles::Left,
+        EditorHandles::Rotate,
+    ];
+
+    // Position of the handle in units of the bounds size.
+    pub fn unit(&self) -> Vec2 {
+        match self {
+            EditorHandles::TopLeft => Vec2::new(0.0, 0.0),
+            EditorHandles::Top => Vec2::new(0.5, 0.0),
+            EditorHandles::TopRight => Vec2::new(1.0, 0.0),
+            EditorHandles::Right => Vec2::new(1.0, 0.5),
+            EditorHandles::BottomRight => Vec2::new(1.0, 1.0),
+            EditorHandles::Bottom => Vec2::new(0.5, 1.0),
+            EditorHandles::BottomLeft => Vec2::new(0.0, 1.0),
+            EditorHandles::Left => Vec2::new(0.0, 0.5),
+            EditorHandles::Rotate => Vec2::new(0.5, 0.5),
+        }
+    }
+
+    // Applies the drag of this handle from `bounds` to `position` on `value`.
+    pub fn apply(
+        &self,
+        bounds: &EditorBounds,
+        position: Vec2,
+        shift: bool,
+        value: &mut EditorValues,
+    ) {
+        if let EditorHandles::Rotate = self {
+            let center = bounds.center();
+            let offset = position - center;
+            // The knob starts straight above the center in the bounds frame.
+            let start = bounds.rotation - PI / 2.0;
+            let mut rotation = bounds.rotation + offset.y.atan2(offset.x) - start;
+            if shift {
+                rotation = (rotation / HANDLE_ROTATE_STEP).round() * HANDLE_ROTATE_STEP;
+            }
+            value.rotate(center, rotation - bounds.rotation);
+            return;
+        }
+
+        let unit = self.unit();
+        let anchor = Vec2::ONE - unit;
+        let handle = unit * bounds.size;
+        let local = bounds.local(position) - anchor * bounds.size;
+        let span = handle - anchor * bounds.size;
+
+        let mut factor = Vec2::ONE;
+        if span.x.abs() > f32::EPSILON {
+            factor.x = (local.x / span.x).max(0.01);
+        }
+        if span.y.abs() > f32::EPSILON {
+            factor.y = (local.y / span.y).max(0.01);
+        }
+        if shift {
+            let uniform = match self {
+                EditorHandles::Top | EditorHandles::Bottom => factor.y,
+                EditorHandles::Left | EditorHandles::Right => factor.x,
+                _ => factor.x.max(factor.y),
+            };
+            factor = Vec2::splat(uniform);
+        }
+        value.scale(bounds.world(anchor), factor, bounds.rotation);
+    }
+}
+
+impl EditorBounds {
+    pub fn new(state: &EditorState) -> Option<Self> {
+        let values: Vec<EditorValues> = state
+            .selection
+            .iter()
+            .filter_map(|i| state.stack.get(*i))
+            .map(|i| i.value.clone())
+            .collect();
+
+        // A single rotated shape is framed in its own orientation.
+        if let [value] = values.as_slice() {
+            match *value {
+                EditorValues::Rectangle {
+                    point,
+                    width,
+                    height,
+                    rotation,
+                } => {
+                    let size = Vec2::new(width, height);
+                    return Some(Self {
+                        point,
+                        size,
+                        rotation,
+                    });
+                }
+                EditorValues::Ellipse {
+                    center,
+                    width,
+                    height,
+                    rotation,
+                } => {
+                    let rotation = rotation.to_radians();
+                    let size = Vec2::new(width, height) * 2.0;
+                    let bounds = Self {
+                        point: Vec2::ZERO,
+                        size,
+                        rotation,
+                    };
+                    let point = center - bounds.world(Vec2::splat(0.5));
+                    return Some(Self {
+                        point,
+                        size,
+                        rotation,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let bounds = values
+            .iter()
+            .map(|i| i.bounds())
+            .reduce(|a, b| a.combine_with(b))?;
+        Some(Self {
+            point: bounds.point(),
+            size: bounds.size(),
+            rotation: 0.0,
+        })
+    }
+
+    // Converts a point given in units of the size to canvas coordinates.
+    pub fn world(&self, unit: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let p = unit * self.size;
+        self.point + Vec2::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+    }
+
+    // Converts canvas coordinates to the unrotated frame of the bounds.
+    pub fn local(&self, position: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let p = position - self.point;
+        Vec2::new(p.x * cos + p.y * sin, -p.x * sin + p.y * cos)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.world(Vec2::splat(0.5))
+    }
+
+    // Position of `handle`, `unit` is the size of a screen pixel so the knob
+    // keeps its distance at any zoom.
+    pub fn handle(&self, handle: EditorHandles, unit: f32) -> Vec2 {
+        match handle {
+            EditorHandles::Rotate => {
+                let (sin, cos) = self.rotation.sin_cos();
+                self.world(Vec2::new(0.5, 0.0)) + Vec2::new(sin, -cos) * HANDLE_ROTATE * unit
+            }
+            _ => self.world(handle.unit()),
+        }
+    }
+
+    pub fn find(&self, position: Vec2, unit: f32) -> Option<EditorHandles> {
+        EditorHandles::ALL
+            .into_iter()
+            .find(|i| position.distance(self.handle(*i, unit)) <= SIZE_POINT * 2.0 * unit)
+    }
+
+    pub fn draw(&self, unit: f32) {
+        let color = SKYBLUE.with_alpha(0.8);
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ]
+        .map(|i| self.world(i));
+        for i in 0..corners.len() {
+            let a = corners[i];
+            let b = corners[(i + 1) % corners.len()];
+            draw_line(a.x, a.y, b.x, b.y, unit, color);
+        }
+
+        let top = self.world(Vec2::new(0.5, 0.0));
+        let knob = self.handle(EditorHandles::Rotate, unit);
+        draw_line(top.x, top.y, knob.x, knob.y, unit, color);
+        draw_circle_lines(knob.x, knob.y, SIZE_POINT * unit, unit, color);
+
+        for handle in EditorHandles::ALL {
+            if handle == EditorHandles::Rotate {
+                continue;
+            }
+            let point = self.handle(handle, unit);
+            let size = SIZE_POINT * unit;
+            draw_rectangle(
+                point.x - size,
+                point.y - size,
+                size * 2.0,
+                size * 2.0,
+                color,
+            );
+        }
+    }
+}
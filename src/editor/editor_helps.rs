@@ -2,95 +2,76 @@ use macroquad::prelude::draw_line;
 use macroquad::prelude::draw_rectangle_lines;
 use macroquad::prelude::draw_text;
 use macroquad::prelude::screen_height;
-use macroquad::prelude::screen_width;
 use macroquad::prelude::GRAY;
 use macroquad::prelude::LIGHTGRAY;
 use macroquad::prelude::RED;
 use macroquad::prelude::YELLOW;
 
 use super::EditorState;
-use super::DISPLAY_SIZE;
-use super::DISPLAY_SIZE_HD;
 use super::SIZE_GRID;
 
 pub struct EditorHelps {}
 
 impl EditorHelps {
     pub fn draw(state: &mut EditorState) {
-        let width = screen_width();
-        let height = screen_height();
+        let unit = state.camera.unit();
+        let view = state.camera.view();
 
         if state.grid > 0 {
-            if state.grid >= 1 {
-                let grid_color = GRAY.with_alpha(0.1);
-                let grid_size = SIZE_GRID;
-                for x in (0..=(width as i32 / grid_size as i32)).map(|i| i as f32 * grid_size) {
-                    draw_line(x, 0.0, x, height, 1.0, grid_color);
-                }
-                for y in (0..=(height as i32 / grid_size as i32)).map(|i| i as f32 * grid_size) {
-                    draw_line(0.0, y, width, y, 1.0, grid_color);
-                }
-            }
-
+            let grid_color = GRAY.with_alpha(0.1);
+            let mut sizes = vec![SIZE_GRID];
             if state.grid == 2 {
-                let grid_color = GRAY.with_alpha(0.1);
-                let grid_size = SIZE_GRID * 5.0;
-                for x in (0..=(width as i32 / grid_size as i32)).map(|i| i as f32 * grid_size) {
-                    draw_line(x, 0.0, x, height, 1.0, grid_color);
+                sizes.push(SIZE_GRID * 5.0);
+            }
+            for grid_size in sizes {
+                // Lines closer than a few pixels would only fill the view.
+                if grid_size / unit < 4.0 {
+                    continue;
+                }
+                let first = (view.x / grid_size).floor() as i32;
+                let last = (view.right() / grid_size).ceil() as i32;
+                for x in (first..=last).map(|i| i as f32 * grid_size) {
+                    draw_line(x, view.y, x, view.bottom(), unit, grid_color);
                 }
-                for y in (0..=(height as i32 / grid_size as i32)).map(|i| i as f32 * grid_size) {
-                    draw_line(0.0, y, width, y, 1.0, grid_color);
+                let first = (view.y / grid_size).floor() as i32;
+                let last = (view.bottom() / grid_size).ceil() as i32;
+                for y in (first..=last).map(|i| i as f32 * grid_size) {
+                    draw_line(view.x, y, view.right(), y, unit, grid_color);
                 }
             }
         }
 
-        let display_x = width / 2.0 - DISPLAY_SIZE.x / 2.0;
-        let display_y = height / 2.0 - DISPLAY_SIZE.y / 2.0;
-        let display_color = RED.with_alpha(0.3);
-        draw_text(
-            format!(
-                "{}X{}",
-                DISPLAY_SIZE.x.round() as u16,
-                DISPLAY_SIZE_HD.y.round() as u16
-            )
-            .as_str(),
-            display_x,
-            display_y - 10.0,
-            18.0,
-            RED.with_alpha(0.5),
-        );
-        draw_rectangle_lines(
-            display_x,
-            display_y,
-            DISPLAY_SIZE.x,
-            DISPLAY_SIZE.y,
-            2.0,
-            display_color,
-        );
+        for (index, artboard) in state.artboards.iter().enumerate() {
+            let rect = artboard.rect();
+            // The artboard commands act on the brighter one.
+            let alpha = if index == state.artboard { 1.0 } else { 0.5 };
+            state.camera.text(
+                artboard.text().as_str(),
+                rect.x,
+                rect.y - 10.0 * unit,
+                18.0,
+                RED.with_alpha(0.5 * alpha),
+            );
+            draw_rectangle_lines(
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                2.0 * unit,
+                RED.with_alpha(0.3 * alpha),
+            );
+        }
 
-        let display_x = width / 2.0 - DISPLAY_SIZE_HD.x / 2.0;
-        let display_y = height / 2.0 - DISPLAY_SIZE_HD.y / 2.0;
-        let display_color = RED.with_alpha(0.3);
-        draw_text(
-            format!(
-                "{}X{}",
-                DISPLAY_SIZE_HD.x.round() as u16,
-                DISPLAY_SIZE_HD.y.round() as u16
-            )
-            .as_str(),
-            display_x,
-            display_y - 10.0,
+        let frame = state.export.frame.rect(&state.stack, &state.artboards);
+        let frame_color = YELLOW.with_alpha(0.3);
+        state.camera.text(
+            format!("EXPORT {}", state.export.frame.text(&state.artboards)).as_str(),
+            frame.x,
+            frame.y + frame.h + 20.0 * unit,
             18.0,
-            RED.with_alpha(0.5),
-        );
-        draw_rectangle_lines(
-            display_x,
-            display_y,
-            DISPLAY_SIZE_HD.x,
-            DISPLAY_SIZE_HD.y,
-            2.0,
-            display_color,
+            frame_color,
         );
+        draw_rectangle_lines(frame.x, frame.y, frame.w, frame.h, unit, frame_color);
     }
     pub fn actions(state: &mut EditorState) {
         if state.help {
@@ -98,27 +79,129 @@ impl EditorHelps {
                 ("HELP", ""),
                 ("[CMD+Z]", "Undo the last action"),
                 ("[CMD+Y]", "Redo the undone action"),
-                ("[CMD+S]", "Toggle snap mode, align to nearby points"),
+                ("[CMD+;]", "Toggle snap mode, align to nearby points"),
                 ("[CMD+G]", "Toggle background grid visibility"),
+                ("[CMD+O]", "Open a document"),
+                ("[CMD+S]", "Save the document"),
+                ("[CMD+SHIFT+S]", "Save the document under a new name"),
+                ("[CMD+I]", "Import shapes from an SVG file"),
+                ("[A]", "Select tool, SHIFT+click adds to the selection"),
+                (
+                    "[M]",
+                    "Toggle marquee selection of inside or touching shapes",
+                ),
+                ("[SHIFT/ALT+DRAG]", "Add to or subtract from the selection"),
+                (
+                    "[D]",
+                    "Toggle vertex mode, drag the points of the selection",
+                ),
+                ("[DRAG HANDLE]", "Resize or rotate the selection"),
+                (
+                    "[SHIFT+HANDLE]",
+                    "Keep proportions or rotate in 15 degree steps",
+                ),
+                ("[DELETE]", "Delete the selection, BACKSPACE works too"),
+                ("[CMD+D]", "Duplicate the selection with an offset"),
+                ("[CMD+C/X]", "Copy or cut the selection"),
+                ("[CMD+V]", "Paste at the original position"),
+                ("[CMD+SHIFT+V]", "Paste at the cursor"),
+                (
+                    "[ENTER]",
+                    "Finish the polyline, polygon or path, or double-click",
+                ),
+                ("[PATH+DRAG]", "Pull out curve handles from the new anchor"),
+                ("[ARC/PIE]", "Drag the radius, then move around and click"),
+                (
+                    "[DRAG+WHEEL]",
+                    "Change the sides of a polygon or star, 3 to 64",
+                ),
+                ("[S]", "Toggle star mode for polygons"),
+                (
+                    "[SHIFT+WHEEL]",
+                    "Change the inner radius of a star while dragging",
+                ),
+                ("[ESC]", "Cancel the shape being placed point by point"),
+                ("[FILL/STROKE]", "Toggle the fill or stroke of shapes"),
+                ("[ / ]", "Thinner or thicker strokes, also the selection"),
+                (
+                    "[CMD+] / [ ]",
+                    "Bring the selection forward or send it backward",
+                ),
+                (
+                    "[CMD+SHIFT+] / [ ]",
+                    "Bring the selection to the front or back",
+                ),
+                ("[WIDTH]", "Type the stroke width"),
+                (
+                    "[COLOR]",
+                    "Open the color picker, SHIFT+click for the stroke",
+                ),
+                ("[PICK]", "Take the color of a shape on the canvas"),
+                ("[#HEX]", "Type a hex or rgba() color in the picker"),
+                (
+                    "[SWATCH]",
+                    "Use a palette color, SHIFT sets it, ALT removes it",
+                ),
+                ("[K]", "Open a palette, .gpl, .hex or .json"),
+                ("[SHIFT+K]", "Save the palette, .rs writes constants"),
+                ("[ALT+K]", "Add the current color to the palette"),
+                ("[CMD+K]", "Set the module path of exported palette colors"),
+                ("[E]", "Export draw code to a Rust file"),
+                ("[SHIFT+E]", "Set the exported function name"),
+                ("[ALT+E]", "Set the exported module path"),
+                ("[CMD+E]", "Set a source file to update in place"),
+                ("[V]", "Export the composition to SVG"),
+                ("[P]", "Export the composition to PNG"),
+                ("[SHIFT+P]", "Set the PNG scale"),
+                ("[ALT+P]", "Set the PNG background, none for transparent"),
+                ("[F]", "Cycle the exported frame, content or an artboard"),
+                ("[L]", "Show or hide the layers panel"),
+                ("[LAYER]", "Make active, drag to reorder, SHIFT renames it"),
+                ("[ALT+LAYER]", "Move the selection into the layer"),
+                (
+                    "[V/L]",
+                    "Show or lock a layer, hidden ones are not exported",
+                ),
+                (
+                    "[SPLIT/JOIN]",
+                    "Export a function per layer or a single one",
+                ),
+                ("[B]", "Cycle the active artboard"),
+                ("[SHIFT+B]", "Rename or resize the active artboard"),
+                ("[ALT+B]", "Add an artboard, e.g. SPRITE 32x32"),
+                ("[CMD+B]", "Remove the active artboard"),
+                ("[WHEEL]", "Zoom around the cursor"),
+                (
+                    "[SPACE+DRAG]",
+                    "Pan the canvas, the middle button works too",
+                ),
+                ("[SHIFT+1]", "Zoom to fit all shapes"),
+                ("[SHIFT+2]", "Zoom to the selection"),
+                ("[SHIFT+0]", "Zoom to 100%"),
+                ("[CMD+=/-]", "Zoom in or out"),
                 ("[H]", "Show or hide this help overlay"),
             ];
 
             let text_size = 20.0;
             let spacing = 6.0;
             let line_height = text_size + spacing;
-            let total_height = help_items.len() as f32 * line_height;
+            let padding = 20.0;
+            let column_width = 560.0;
 
+            // Items wrap into more columns when the window is too short.
+            let rows = ((screen_height() - padding * 2.0) / line_height).max(1.0) as usize;
+            let total_height = help_items.len().min(rows) as f32 * line_height;
             let start_y = screen_height() / 2.0 - total_height / 2.0;
-            let padding = 20.0;
 
             for (i, (shortcut, description)) in help_items.iter().enumerate() {
-                let y = start_y + i as f32 * line_height;
+                let x = padding + (i / rows) as f32 * column_width;
+                let y = start_y + (i % rows) as f32 * line_height;
 
                 if description.is_empty() {
-                    draw_text(shortcut, padding, y, text_size, YELLOW);
+                    draw_text(shortcut, x, y, text_size, YELLOW);
                 } else {
-                    draw_text(shortcut, padding, y, text_size, LIGHTGRAY);
-                    draw_text(description, padding + 80.0, y, text_size, GRAY);
+                    draw_text(shortcut, x, y, text_size, LIGHTGRAY);
+                    draw_text(description, x + 160.0, y, text_size, GRAY);
                 }
             }
         }
@@ -0,0 +1,436 @@
+use macroquad::prelude::draw_line;
+use macroquad::prelude::draw_rectangle;
+use macroquad::prelude::draw_text;
+use macroquad::prelude::is_key_down;
+use macroquad::prelude::is_mouse_button_pressed;
+use macroquad::prelude::is_mouse_button_released;
+use macroquad::prelude::measure_text;
+use macroquad::prelude::mouse_position;
+use macroquad::prelude::screen_width;
+use macroquad::prelude::KeyCode;
+use macroquad::prelude::MouseButton;
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use macroquad::prelude::BLACK;
+use macroquad::prelude::DARKGRAY;
+use macroquad::prelude::GRAY;
+use macroquad::prelude::GREEN;
+use macroquad::prelude::LIGHTGRAY;
+use macroquad::prelude::WHITE;
+use macroquad::prelude::YELLOW;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::EditorPrompt;
+use super::EditorPrompts;
+use super::EditorState;
+
+const LAYER_WIDTH: f32 = 200.0;
+const LAYER_ROW: f32 = 20.0;
+const LAYER_PADDING: f32 = 10.0;
+const LAYER_TEXT: f32 = 16.0;
+
+// Named group of elements drawn in list order, elements refer to it by `id`
+// so it survives renames and reordering.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorLayer {
+    pub id: usize,
+    pub name: String,
+    pub visible: bool,
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorLayerParts {
+    Add,
+    Remove,
+    Split,
+    // Parts of the row of the layer at an index, the top row is the last one.
+    Visible(usize),
+    Locked(usize),
+    Name(usize),
+}
+
+impl EditorLayer {
+    pub fn new(id: usize, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            visible: true,
+            locked: false,
+        }
+    }
+
+    // Documents from before layers keep all elements in one.
+    pub fn defaults() -> Vec<Self> {
+        vec![EditorLayer::new(0, "LAYER 1")]
+    }
+
+    // Draw position of the layer `id`, elements of missing layers go first.
+    pub fn order(layers: &[Self], id: usize) -> usize {
+        layers.iter().position(|i| i.id == id).unwrap_or(0)
+    }
+
+    // Name of the exported function drawing the layer, `draw` and `Enemy
+    // hull` give `draw_enemy_hull`.
+    pub fn function(&self, name: &str) -> String {
+        let words = self
+            .name
+            .split(|i: char| !i.is_ascii_alphanumeric())
+            .filter(|i| !i.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        if words.is_empty() {
+            format!("{}_layer_{}", name, self.id)
+        } else {
+            format!("{}_{}", name, words.join("_"))
+        }
+    }
+
+    // Function names of all layers in order, names already taken get a
+    // number, `draw_hull` and `draw_hull_2`.
+    pub fn functions(layers: &[Self], name: &str) -> Vec<String> {
+        let mut functions: Vec<String> = Vec::new();
+        for layer in layers {
+            let function = layer.function(name);
+            let mut unique = function.clone();
+            for i in 2.. {
+                if !functions.contains(&unique) {
+                    break;
+                }
+                unique = format!("{}_{}", function, i);
+            }
+            functions.push(unique);
+        }
+        functions
+    }
+
+    // Panel in screen space, below the zoom buttons.
+    pub fn panel(layers: usize) -> Rect {
+        let height = LAYER_PADDING * 2.0 + LAYER_ROW * (layers + 1) as f32;
+        Rect::new(
+            screen_width() - LAYER_WIDTH - 10.0,
+            40.0,
+            LAYER_WIDTH,
+            height,
+        )
+    }
+
+    // Whether the pointer belongs to the panel instead of the canvas.
+    pub fn blocks(state: &EditorState) -> bool {
+        let position: Vec2 = mouse_position().into();
+        state.layer_panel
+            && (state.layer_drag.is_some()
+                || EditorLayer::panel(state.layers.len()).contains(position))
+    }
+
+    pub fn draw(state: &mut EditorState) {
+        if !state.layer_panel {
+            return;
+        }
+        let position: Vec2 = mouse_position().into();
+        let count = state.layers.len();
+        let hover = EditorLayerParts::find(position, count);
+        let panel = EditorLayer::panel(count);
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, BLACK.with_alpha(0.8));
+
+        let title = EditorLayerParts::Add.rect(count);
+        draw_text(
+            "LAYERS",
+            panel.x + LAYER_PADDING,
+            title.bottom(),
+            LAYER_TEXT,
+            GRAY,
+        );
+        for part in [
+            EditorLayerParts::Add,
+            EditorLayerParts::Remove,
+            EditorLayerParts::Split,
+        ] {
+            let rect = part.rect(count);
+            let color = if hover == Some(part) {
+                LIGHTGRAY
+            } else if part == EditorLayerParts::Remove && count < 2 {
+                DARKGRAY
+            } else {
+                GRAY
+            };
+            let text = part.text(state.export.split);
+            draw_text(text, rect.x, rect.bottom(), LAYER_TEXT, color);
+        }
+
+        for (index, layer) in state.layers.iter().enumerate() {
+            let row = EditorLayerParts::Name(index).rect(count);
+            if index == state.layer {
+                draw_rectangle(panel.x, row.y, panel.w, row.h, GRAY.with_alpha(0.3));
+            }
+            for (part, on, color) in [
+                (EditorLayerParts::Visible(index), layer.visible, GREEN),
+                (EditorLayerParts::Locked(index), layer.locked, YELLOW),
+            ] {
+                let rect = part.rect(count);
+                let color = if hover == Some(part) {
+                    LIGHTGRAY
+                } else if on {
+                    color
+                } else {
+                    DARKGRAY
+                };
+                draw_text(
+                    part.text(false),
+                    rect.x,
+                    rect.bottom() - 3.0,
+                    LAYER_TEXT,
+                    color,
+                );
+            }
+            let color = if !layer.visible {
+                DARKGRAY
+            } else if hover == Some(EditorLayerParts::Name(index)) {
+                LIGHTGRAY
+            } else {
+                GRAY
+            };
+            draw_text(&layer.name, row.x, row.bottom() - 3.0, LAYER_TEXT, color);
+        }
+
+        // Where a dragged layer would land.
+        if let Some(drag) = state.layer_drag {
+            if let Some(target) = EditorLayerParts::row(position, count).filter(|i| *i != drag) {
+                let rect = EditorLayerParts::Name(target).rect(count);
+                let y = if target > drag { rect.y } else { rect.bottom() };
+                draw_line(panel.x, y, panel.right(), y, 2.0, WHITE);
+            }
+        }
+    }
+
+    pub fn actions(state: &mut EditorState) {
+        if state.prompt.is_some() || !state.layer_panel {
+            return;
+        }
+        let position: Vec2 = mouse_position().into();
+        let count = state.layers.len();
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            match EditorLayerParts::find(position, count) {
+                Some(EditorLayerParts::Add) => state.layer_add(),
+                Some(EditorLayerParts::Remove) => state.layer_remove(),
+                Some(EditorLayerParts::Split) => state.export.split = !state.export.split,
+                Some(EditorLayerParts::Visible(i)) => state.layer_visible(i),
+                Some(EditorLayerParts::Locked(i)) => state.layer_lock(i),
+                // SHIFT renames the layer, ALT moves the selection into it.
+                Some(EditorLayerParts::Name(i)) => {
+                    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                    let alt = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+                    if alt {
+                        state.layer_move(i);
+                    } else {
+                        state.layer = i;
+                        if shift {
+                            let text = state.layers[i].name.clone();
+                            state.prompt = Some(EditorPrompt::new(EditorPrompts::LayerName, text));
+                        } else {
+                            state.layer_drag = Some(i);
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            if let Some(drag) = state.layer_drag.take() {
+                if let Some(target) = EditorLayerParts::row(position, count) {
+                    state.layer_reorder(drag, target);
+                }
+            }
+        }
+    }
+}
+
+impl EditorLayerParts {
+    fn text(&self, split: bool) -> &str {
+        match self {
+            EditorLayerParts::Add => "+",
+            EditorLayerParts::Remove => "-",
+            // Export as one function per layer or a single one.
+            EditorLayerParts::Split if split => "SPLIT",
+            EditorLayerParts::Split => "JOIN",
+            EditorLayerParts::Visible(_) => "V",
+            EditorLayerParts::Locked(_) => "L",
+            EditorLayerParts::Name(_) => "",
+        }
+    }
+
+    // `layers` is the number of layers, the panel grows with it.
+    pub fn rect(&self, layers: usize) -> Rect {
+        let panel = EditorLayer::panel(layers);
+        let x = panel.x + LAYER_PADDING;
+        let y = panel.y + LAYER_PADDING;
+        let right = panel.right() - LAYER_PADDING;
+        let width = |text: &str| measure_text(text, None, LAYER_TEXT as u16, 1.0).width;
+        let row = |i: usize| y + LAYER_ROW * (layers - i) as f32;
+        match *self {
+            EditorLayerParts::Split => {
+                let width = width("SPLIT");
+                Rect::new(right - width, y, width, LAYER_TEXT)
+            }
+            EditorLayerParts::Remove => {
+                let split = EditorLayerParts::Split.rect(layers);
+                Rect::new(
+                    split.x - LAYER_PADDING - width("-"),
+                    y,
+                    width("-"),
+                    LAYER_TEXT,
+                )
+            }
+            EditorLayerParts::Add => {
+                let remove = EditorLayerParts::Remove.rect(layers);
+                Rect::new(
+                    remove.x - LAYER_PADDING - width("+"),
+                    y,
+                    width("+"),
+                    LAYER_TEXT,
+                )
+            }
+            EditorLayerParts::Visible(i) => Rect::new(x, row(i), LAYER_TEXT, LAYER_ROW),
+            EditorLayerParts::Locked(i) => Rect::new(x + LAYER_TEXT, row(i), LAYER_TEXT, LAYER_ROW),
+            EditorLayerParts::Name(i) => {
+                let x = x + LAYER_TEXT * 2.0 + LAYER_PADDING / 2.0;
+                Rect::new(x, row(i), right - x, LAYER_ROW)
+            }
+        }
+    }
+
+    pub fn find(position: Vec2, layers: usize) -> Option<Self> {
+        [
+            EditorLayerParts::Add,
+            EditorLayerParts::Remove,
+            EditorLayerParts::Split,
+        ]
+        .into_iter()
+        .chain((0..layers).flat_map(|i| {
+            [
+                EditorLayerParts::Visible(i),
+                EditorLayerParts::Locked(i),
+                EditorLayerParts::Name(i),
+            ]
+        }))
+        .find(|i| i.rect(layers).contains(position))
+    }
+
+    // Layer of the row under `position`, rows span the whole panel width.
+    fn row(position: Vec2, layers: usize) -> Option<usize> {
+        let panel = EditorLayer::panel(layers);
+        (0..layers).find(|i| {
+            let rect = EditorLayerParts::Name(*i).rect(layers);
+            position.y >= rect.y
+                && position.y < rect.bottom()
+                && position.x >= panel.x
+                && position.x <= panel.right()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+    use macroquad::prelude::RED;
+
+    use super::EditorLayer;
+    use crate::editor::EditorElement;
+    use crate::editor::EditorExport;
+    use crate::editor::EditorPalette;
+    use crate::editor::EditorState;
+    use crate::editor::EditorValues;
+
+    #[test]
+    fn layer_functions_extend_the_export_name() {
+        assert_eq!(EditorLayer::new(0, "HULL").function("draw"), "draw_hull");
+        assert_eq!(
+            EditorLayer::new(1, " Enemy  glow! ").function("draw_ship"),
+            "draw_ship_enemy_glow"
+        );
+        assert_eq!(EditorLayer::new(2, "--").function("draw"), "draw_layer_2");
+
+        let layers = [
+            EditorLayer::new(0, "ENEMY HULL"),
+            EditorLayer::new(1, "ENEMY-HULL"),
+            EditorLayer::new(2, "--"),
+            EditorLayer::new(3, "LAYER 2"),
+        ];
+        assert_eq!(
+            EditorLayer::functions(&layers, "draw"),
+            [
+                "draw_enemy_hull",
+                "draw_enemy_hull_2",
+                "draw_layer_2",
+                "draw_layer_2_2"
+            ]
+        );
+    }
+
+    #[test]
+    fn removed_layers_come_back_on_undo() {
+        let mut state = EditorState::new();
+        state.layer_add();
+        state.layer_rename("glow");
+        state.layer_lock(1);
+        let value = EditorValues::Circle {
+            center: Vec2::new(10.0, 10.0),
+            radius: 10.0,
+        };
+        state.add(EditorElement::new(value, Some(RED), None, 1.0));
+        let layers = state.layers.clone();
+        let stack = state.stack.clone();
+
+        state.layer_remove();
+        assert_eq!(state.layers.len(), 1);
+        assert!(state.stack.is_empty());
+        state.undo();
+        assert_eq!(state.layers, layers);
+        assert_eq!(state.stack, stack);
+    }
+
+    #[test]
+    fn layer_names_stay_unique() {
+        let mut state = EditorState::new();
+        state.layer_add();
+        state.layer = 0;
+        state.layer_remove();
+        state.layer_add();
+        let names: Vec<_> = state.layers.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["LAYER 2", "LAYER 3"]);
+        state.layer_rename("layer 2");
+        assert_eq!(state.layers[1].name, "LAYER 3");
+    }
+
+    #[test]
+    fn split_exports_call_a_function_per_layer() {
+        let layers = vec![
+            EditorLayer::new(0, "HULL"),
+            EditorLayer::new(1, "EMPTY"),
+            EditorLayer::new(2, "GLOW"),
+        ];
+        let element = |layer| {
+            let value = EditorValues::Circle {
+                center: Vec2::new(10.0, 10.0),
+                radius: 10.0,
+            };
+            let mut element = EditorElement::new(value, Some(RED), None, 1.0);
+            element.layer = layer;
+            element
+        };
+        let stack = [element(0), element(2)];
+        let export = EditorExport {
+            split: true,
+            ..EditorExport::default()
+        };
+        let code = export.code(&stack, &layers, &[], &EditorPalette::default());
+        assert!(code.contains("pub fn draw_hull(x: f32, y: f32) {"));
+        assert!(!code.contains("draw_empty"));
+        // The bounds guide goes in the main function only.
+        assert!(code.contains("pub fn draw(x: f32, y: f32) {\n   draw_rectangle_lines("));
+        assert!(code.contains(");\n   draw_hull(x, y);\n   draw_glow(x, y);\n}"));
+        assert_eq!(code.matches("draw_rectangle_lines").count(), 1);
+    }
+}
@@ -0,0 +1,47 @@
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorMarquees {
+    Inside,
+    Touching,
+}
+
+impl EditorMarquees {
+    pub fn text(&self) -> &str {
+        match self {
+            EditorMarquees::Inside => "INSIDE",
+            EditorMarquees::Touching => "TOUCHING",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            EditorMarquees::Inside => EditorMarquees::Touching,
+            EditorMarquees::Touching => EditorMarquees::Inside,
+        }
+    }
+
+    pub fn rect(start: Vec2, position: Vec2) -> Rect {
+        let min = start.min(position);
+        let max = start.max(position);
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    pub fn matches(&self, marquee: Rect, bounds: Rect) -> bool {
+        match self {
+            EditorMarquees::Inside => {
+                bounds.x >= marquee.x
+                    && bounds.y >= marquee.y
+                    && bounds.right() <= marquee.right()
+                    && bounds.bottom() <= marquee.bottom()
+            }
+            EditorMarquees::Touching => {
+                bounds.x <= marquee.right()
+                    && bounds.right() >= marquee.x
+                    && bounds.y <= marquee.bottom()
+                    && bounds.bottom() >= marquee.y
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Draw order changes for the selection, elements stay within their layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorOrders {
+    Forward,
+    Backward,
+    Front,
+    Back,
+}
+
+impl EditorOrders {
+    // New stack order as indices into the old stack. `layers` has the layer
+    // of each element, which the stack is sorted by.
+    pub fn order(&self, layers: &[usize], selection: &[usize]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..layers.len()).collect();
+        let selected = |i: usize| selection.contains(&i);
+        match self {
+            // Selected elements step over the next unselected one, from the
+            // top so that neighbours move together.
+            EditorOrders::Forward => {
+                for i in (1..order.len()).rev() {
+                    let (a, b) = (order[i - 1], order[i]);
+                    if selected(a) && !selected(b) && layers[a] == layers[b] {
+                        order.swap(i - 1, i);
+                    }
+                }
+            }
+            EditorOrders::Backward => {
+                for i in 1..order.len() {
+                    let (a, b) = (order[i - 1], order[i]);
+                    if selected(b) && !selected(a) && layers[a] == layers[b] {
+                        order.swap(i - 1, i);
+                    }
+                }
+            }
+            EditorOrders::Front => order.sort_by_key(|i| (layers[*i], selected(*i))),
+            EditorOrders::Back => order.sort_by_key(|i| (layers[*i], !selected(*i))),
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EditorOrders;
+
+    #[test]
+    fn selection_moves_within_its_layer() {
+        // Two layers of three elements, 1 and 2 selected in the first.
+        let layers = [0, 0, 0, 1, 1, 1];
+        let selection = [1, 2];
+        assert_eq!(
+            EditorOrders::Forward.order(&layers, &selection),
+            [0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            EditorOrders::Backward.order(&layers, &selection),
+            [1, 2, 0, 3, 4, 5]
+        );
+        assert_eq!(
+            EditorOrders::Back.order(&layers, &selection),
+            [1, 2, 0, 3, 4, 5]
+        );
+        assert_eq!(
+            EditorOrders::Front.order(&layers, &[0, 4]),
+            [1, 2, 0, 3, 5, 4]
+        );
+        assert_eq!(
+            EditorOrders::Forward.order(&layers, &[0, 3]),
+            [1, 0, 2, 4, 3, 5]
+        );
+    }
+}
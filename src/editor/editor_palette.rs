@@ -0,0 +1,425 @@
+use macroquad::prelude::Color;
+use serde::Deserialize;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::EditorColor;
+use super::EditorPicker;
+use super::EditorSvg;
+
+pub const PALETTE_EXTENSION: &str = "gpl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorPaletteFormats {
+    // GIMP palettes, colors are opaque.
+    Gpl,
+    // One `rrggbb` or `rrggbbaa` per line with an optional name after it.
+    Hex,
+    // `{"name": ..., "colors": [{"name": ..., "color": "#rrggbbaa"}]}`.
+    Json,
+    // Constants for the game, written only.
+    Rust,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorSwatch {
+    pub name: String,
+    #[serde(with = "EditorColor")]
+    pub color: Color,
+}
+
+// Named colors elements can refer to, exported as constants of a palette
+// module so recoloring happens in one place.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditorPalette {
+    pub name: String,
+    pub swatches: Vec<EditorSwatch>,
+}
+
+// Shape of JSON palette files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EditorPaletteJson {
+    #[serde(default)]
+    name: String,
+    colors: Vec<EditorSwatchJson>,
+}
+
+// Colors are `#rrggbbaa` or any color SVG accepts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EditorSwatchJson {
+    #[serde(default)]
+    name: String,
+    color: String,
+}
+
+impl EditorPaletteFormats {
+    pub fn new(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|i| i.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "hex" | "txt" => EditorPaletteFormats::Hex,
+            "json" => EditorPaletteFormats::Json,
+            "rs" => EditorPaletteFormats::Rust,
+            _ => EditorPaletteFormats::Gpl,
+        }
+    }
+}
+
+impl EditorSwatch {
+    pub fn new(name: &str, color: Color) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+        }
+    }
+}
+
+impl EditorPalette {
+    pub fn color(&self, name: &str) -> Option<Color> {
+        self.swatches
+            .iter()
+            .find(|i| i.name == name)
+            .map(|i| i.color)
+    }
+
+    // Replaces the color of the swatch called `name` or adds a new one.
+    pub fn set(&mut self, name: &str, color: Color) {
+        match self.swatches.iter_mut().find(|i| i.name == name) {
+            Some(swatch) => swatch.color = color,
+            None => self.swatches.push(EditorSwatch::new(name, color)),
+        }
+    }
+
+    // Rust constant name of a swatch, `Enemy hull` becomes `ENEMY_HULL`.
+    pub fn constant(name: &str) -> String {
+        let mut constant = String::new();
+        for char in name.trim().chars() {
+            if char.is_ascii_alphanumeric() {
+                constant.push(char.to_ascii_uppercase());
+            } else if !constant.is_empty() && !constant.ends_with('_') {
+                constant.push('_');
+            }
+        }
+        let constant = constant.trim_end_matches('_').to_string();
+        if constant.is_empty() {
+            "COLOR".to_string()
+        } else if constant.starts_with(|i: char| i.is_ascii_digit()) {
+            format!("_{}", constant)
+        } else {
+            constant
+        }
+    }
+
+    // Constant names of all swatches in order, names already taken get a
+    // number, `ENEMY_HULL` and `ENEMY_HULL_2`.
+    pub fn constants(&self) -> Vec<String> {
+        let mut constants: Vec<String> = Vec::new();
+        for swatch in self.swatches.iter() {
+            let constant = EditorPalette::constant(&swatch.name);
+            let mut unique = constant.clone();
+            for i in 2.. {
+                if !constants.contains(&unique) {
+                    break;
+                }
+                unique = format!("{}_{}", constant, i);
+            }
+            constants.push(unique);
+        }
+        constants
+    }
+
+    // Constant of the swatch called `name`, `None` when it is gone.
+    pub fn reference(&self, name: &str) -> Option<String> {
+        let index = self.swatches.iter().position(|i| i.name == name)?;
+        self.constants().into_iter().nth(index)
+    }
+
+    // Name given to swatches read without one.
+    fn unnamed(index: usize) -> String {
+        format!("COLOR_{}", index + 1)
+    }
+
+    pub fn parse(content: &str, format: EditorPaletteFormats) -> Result<Self, Box<dyn Error>> {
+        let mut palette = EditorPalette::default();
+        match format {
+            EditorPaletteFormats::Gpl => {
+                let mut lines = content.lines();
+                if lines.next().map(str::trim) != Some("GIMP Palette") {
+                    return Err("Missing GIMP Palette header".into());
+                }
+                for line in lines {
+                    let line = line.trim();
+                    if let Some(name) = line.strip_prefix("Name:") {
+                        palette.name = name.trim().to_string();
+                        continue;
+                    }
+                    if line.is_empty() || line.starts_with('#') || line.contains(':') {
+                        continue;
+                    }
+                    let mut parts = line.split_whitespace();
+                    let mut channel = || -> Result<u8, Box<dyn Error>> {
+                        let part = parts.next().ok_or("Missing color channel")?;
+                        Ok(part
+                            .parse::<u8>()
+                            .map_err(|e| format!("Failed to parse {}: {}", part, e))?)
+                    };
+                    let color = Color::from_rgba(channel()?, channel()?, channel()?, 255);
+                    let name = parts.collect::<Vec<_>>().join(" ");
+                    let name = match name.is_empty() || name == "Untitled" {
+                        true => EditorPalette::unnamed(palette.swatches.len()),
+                        false => name,
+                    };
+                    palette.set(&name, color);
+                }
+            }
+            EditorPaletteFormats::Hex => {
+                for line in content.lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with(';') || line.starts_with("//") {
+                        continue;
+                    }
+                    let (hex, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                    let hex = format!("#{}", hex.trim_start_matches('#'));
+                    let color = EditorSvg::parse_color(&hex)
+                        .ok_or(format!("Failed to parse color {}", hex))?;
+                    let name = match name.trim() {
+                        "" => EditorPalette::unnamed(palette.swatches.len()),
+                        name => name.to_string(),
+                    };
+                    palette.set(&name, color);
+                }
+            }
+            EditorPaletteFormats::Json => {
+                let json: EditorPaletteJson = serde_json::from_str(content)?;
+                palette.name = json.name;
+                for i in json.colors {
+                    let color = EditorSvg::parse_color(&i.color)
+                        .ok_or(format!("Failed to parse color {}", i.color))?;
+                    let name = match i.name.is_empty() {
+                        true => EditorPalette::unnamed(palette.swatches.len()),
+                        false => i.name,
+                    };
+                    palette.set(&name, color);
+                }
+            }
+            EditorPaletteFormats::Rust => {
+                return Err("Rust palettes can only be written".into());
+            }
+        }
+        Ok(palette)
+    }
+
+    pub fn text(&self, format: EditorPaletteFormats) -> String {
+        let mut content = String::new();
+        let channel = |i: f32| (i.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match format {
+            EditorPaletteFormats::Gpl => {
+                content.push_str("GIMP Palette\n");
+                content.push_str(&format!("Name: {}\n", self.name));
+                content.push_str("#\n");
+                for i in self.swatches.iter() {
+                    content.push_str(&format!(
+                        "{:3} {:3} {:3}\t{}\n",
+                        channel(i.color.r),
+                        channel(i.color.g),
+                        channel(i.color.b),
+                        i.name
+                    ));
+                }
+            }
+            EditorPaletteFormats::Hex => {
+                for i in self.swatches.iter() {
+                    let hex = EditorPicker::hex(i.color);
+                    // Opaque colors keep the plain six digits other tools read.
+                    let hex = match hex.strip_suffix("ff") {
+                        Some(opaque) => &opaque[1..],
+                        None => &hex[1..],
+                    };
+                    content.push_str(&format!("{} {}\n", hex, i.name));
+                }
+            }
+            EditorPaletteFormats::Json => {
+                let json = EditorPaletteJson {
+                    name: self.name.clone(),
+                    colors: self
+                        .swatches
+                        .iter()
+                        .map(|i| EditorSwatchJson {
+                            name: i.name.clone(),
+                            color: EditorPicker::hex(i.color),
+                        })
+                        .collect(),
+                };
+                // Plain strings and lists always serialize.
+                content.push_str(&serde_json::to_string_pretty(&json).unwrap_or_default());
+                content.push('\n');
+            }
+            EditorPaletteFormats::Rust => {
+                content.push_str("use macroquad::prelude::Color;\n\n");
+                for (i, constant) in self.swatches.iter().zip(self.constants()) {
+                    content.push_str(&format!(
+                        "pub const {}: Color = Color::new({:.3}, {:.3}, {:.3}, {:.3});\n",
+                        constant, i.color.r, i.color.g, i.color.b, i.color.a
+                    ));
+                }
+            }
+        }
+        content
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut palette = EditorPalette::parse(&content, EditorPaletteFormats::new(path))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        if palette.name.is_empty() {
+            palette.name = path
+                .file_stem()
+                .map(|i| i.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(palette)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.text(EditorPaletteFormats::new(path)))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Color;
+    use macroquad::prelude::Vec2;
+
+    use super::EditorPalette;
+    use super::EditorPaletteFormats;
+    use super::EditorSwatch;
+    use crate::editor::EditorElement;
+    use crate::editor::EditorExport;
+    use crate::editor::EditorPickers;
+    use crate::editor::EditorState;
+    use crate::editor::EditorValues;
+
+    #[test]
+    fn reads_and_writes_every_format() {
+        let palette = EditorPalette {
+            name: "Enemies".to_string(),
+            swatches: vec![
+                EditorSwatch::new("Enemy hull", Color::from_rgba(255, 0, 0, 255)),
+                EditorSwatch::new("SHIELD", Color::from_rgba(0, 128, 255, 128)),
+            ],
+        };
+        for format in [EditorPaletteFormats::Hex, EditorPaletteFormats::Json] {
+            let text = palette.text(format);
+            assert_eq!(
+                EditorPalette::parse(&text, format).unwrap().swatches,
+                palette.swatches
+            );
+        }
+        // GIMP palettes drop the alpha.
+        let text = palette.text(EditorPaletteFormats::Gpl);
+        let gpl = EditorPalette::parse(&text, EditorPaletteFormats::Gpl).unwrap();
+        assert_eq!(gpl.name, "Enemies");
+        assert_eq!(gpl.swatches[1].color, Color::from_rgba(0, 128, 255, 255));
+
+        let hex = EditorPalette::parse("ff0000\n00ff00\n", EditorPaletteFormats::Hex).unwrap();
+        assert_eq!(hex.swatches[1].name, "COLOR_2");
+        let json = r##"{"colors": [{"color": "#ff0000"}, {"name": "ENGINE \"2\"\n", "color": "rgb(0, 0, 255)"}]}"##;
+        let json = EditorPalette::parse(json, EditorPaletteFormats::Json).unwrap();
+        assert_eq!(json.swatches[0].name, "COLOR_1");
+        assert_eq!(
+            json.color("ENGINE \"2\"\n"),
+            Some(Color::from_rgba(0, 0, 255, 255))
+        );
+        // Control characters in names are escaped.
+        let mut escaped = palette.clone();
+        escaped.swatches[0].name = "TAB\tBELL\u{7}".to_string();
+        let text = escaped.text(EditorPaletteFormats::Json);
+        assert!(text.contains("TAB\\tBELL\\u0007"));
+        let parsed = EditorPalette::parse(&text, EditorPaletteFormats::Json).unwrap();
+        assert_eq!(parsed.swatches, escaped.swatches);
+
+        assert_eq!(EditorPalette::constant("Enemy hull"), "ENEMY_HULL");
+        assert_eq!(EditorPalette::constant(" 2nd - stripe "), "_2ND_STRIPE");
+        assert_eq!(EditorPalette::constant("--"), "COLOR");
+        let clashing = EditorPalette {
+            name: String::new(),
+            swatches: vec![
+                EditorSwatch::new("Enemy hull", Color::from_rgba(255, 0, 0, 255)),
+                EditorSwatch::new("ENEMY-HULL", Color::from_rgba(0, 255, 0, 255)),
+                EditorSwatch::new("?", Color::from_rgba(0, 0, 255, 255)),
+                EditorSwatch::new("", Color::from_rgba(0, 0, 0, 255)),
+            ],
+        };
+        assert_eq!(
+            clashing.constants(),
+            ["ENEMY_HULL", "ENEMY_HULL_2", "COLOR", "COLOR_2"]
+        );
+        assert_eq!(
+            clashing.reference("ENEMY-HULL").as_deref(),
+            Some("ENEMY_HULL_2")
+        );
+        assert_eq!(clashing.reference("Gone"), None);
+        let rust = palette.text(EditorPaletteFormats::Rust);
+        assert!(
+            rust.contains("pub const ENEMY_HULL: Color = Color::new(1.000, 0.000, 0.000, 1.000);")
+        );
+    }
+
+    #[test]
+    fn code_refers_to_palette_constants() {
+        let red = Color::from_rgba(255, 0, 0, 255);
+        let palette = EditorPalette {
+            name: String::new(),
+            swatches: vec![EditorSwatch::new("Enemy hull", red)],
+        };
+        let value = EditorValues::Circle {
+            center: Vec2::new(10.0, 10.0),
+            radius: 10.0,
+        };
+        let mut element = EditorElement::new(value, Some(red), Some(red), 1.0);
+        element.fill_swatch = Some("Enemy hull".to_string());
+        element.stroke_swatch = Some("Gone".to_string());
+        let code = EditorExport::default().code(&[element], &[], &[], &palette);
+        assert!(code.contains("draw_circle(x + 10.5, y + 10.5, 10.0, palette::ENEMY_HULL);"));
+        // Entries missing from the palette fall back to the color.
+        assert!(code.contains("9.5, 1.0, Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 });"));
+    }
+
+    #[test]
+    fn palette_changes_undo_with_the_elements() {
+        let red = Color::from_rgba(255, 0, 0, 255);
+        let blue = Color::from_rgba(0, 0, 255, 255);
+        let mut state = EditorState::new();
+        let value = EditorValues::Circle {
+            center: Vec2::new(10.0, 10.0),
+            radius: 10.0,
+        };
+        state.add(EditorElement::new(value, Some(red), None, 1.0));
+        state.selection = vec![0];
+        state.element_color = red;
+        state.palette_add("HULL");
+        assert_eq!(state.stack[0].fill_swatch.as_deref(), Some("HULL"));
+
+        state.palette_set(0, blue);
+        assert_eq!(state.stack[0].fill, Some(blue));
+        state.undo();
+        assert_eq!(state.palette.color("HULL"), Some(red));
+        assert_eq!(state.stack[0].fill, Some(red));
+        assert_eq!(state.color(EditorPickers::Fill), red);
+        state.redo();
+        assert_eq!(state.palette.color("HULL"), Some(blue));
+        assert_eq!(state.stack[0].fill, Some(blue));
+
+        state.undo();
+        state.undo();
+        assert!(state.palette.swatches.is_empty());
+        assert_eq!(state.stack[0].fill_swatch, None);
+    }
+}
@@ -0,0 +1,468 @@
+use macroquad::prelude::Vec2;
+use serde::Deserialize;
+use serde::Serialize;
+use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::PI;
+
+// Largest distance allowed between a curve and its flattened lines.
+pub const FLATTEN_TOLERANCE: f32 = 0.25;
+// Subdivision limit when flattening a single curve.
+const FLATTEN_DEPTH: u32 = 10;
+
+// Segments of a path, each starts where the previous one ends.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EditorSegments {
+    Line {
+        point: Vec2,
+    },
+    Quadratic {
+        control: Vec2,
+        point: Vec2,
+    },
+    Cubic {
+        control_a: Vec2,
+        control_b: Vec2,
+        point: Vec2,
+    },
+}
+
+// Role of an editable point of a path, nodes count the start as `0` and the
+// end of segment `i` as `i + 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditorPathPoints {
+    Anchor(usize),
+    In(usize),
+    Out(usize),
+    Control(usize),
+}
+
+impl EditorSegments {
+    // Segment from the last anchor to `point` using the handles around it,
+    // a single handle makes a quadratic curve.
+    pub fn new(out: Option<Vec2>, into: Option<Vec2>, point: Vec2) -> Self {
+        match (out, into) {
+            (Some(control_a), Some(control_b)) => EditorSegments::Cubic {
+                control_a,
+                control_b,
+                point,
+            },
+            (Some(control), None) | (None, Some(control)) => {
+                EditorSegments::Quadratic { control, point }
+            }
+            (None, None) => EditorSegments::Line { point },
+        }
+    }
+
+    pub fn point(&self) -> Vec2 {
+        match *self {
+            EditorSegments::Line { point }
+            | EditorSegments::Quadratic { point, .. }
+            | EditorSegments::Cubic { point, .. } => point,
+        }
+    }
+
+    pub fn map(&mut self, transform: impl Fn(Vec2) -> Vec2) {
+        match self {
+            EditorSegments::Line { point } => {
+                *point = transform(*point);
+            }
+            EditorSegments::Quadratic { control, point } => {
+                *control = transform(*control);
+                *point = transform(*point);
+            }
+            EditorSegments::Cubic {
+                control_a,
+                control_b,
+                point,
+            } => {
+                *control_a = transform(*control_a);
+                *control_b = transform(*control_b);
+                *point = transform(*point);
+            }
+        }
+    }
+
+    // Points along the whole path, curves are subdivided until they stay
+    // within `FLATTEN_TOLERANCE` of their chords.
+    pub fn flatten(start: Vec2, segments: &[EditorSegments]) -> Vec<Vec2> {
+        let mut points = vec![start];
+        let mut from = start;
+        for i in segments {
+            match *i {
+                EditorSegments::Line { point } => points.push(point),
+                EditorSegments::Quadratic { control, point } => {
+                    // Quadratic curves are flattened as their cubic equivalent.
+                    let control_a = from + (control - from) * 2.0 / 3.0;
+                    let control_b = point + (control - point) * 2.0 / 3.0;
+                    EditorSegments::cubic(from, control_a, control_b, point, 0, &mut points);
+                }
+                EditorSegments::Cubic {
+                    control_a,
+                    control_b,
+                    point,
+                } => EditorSegments::cubic(from, control_a, control_b, point, 0, &mut points),
+            }
+            from = i.point();
+        }
+        points
+    }
+
+    fn cubic(a: Vec2, b: Vec2, c: Vec2, d: Vec2, depth: u32, points: &mut Vec<Vec2>) {
+        let flat = EditorSegments::distance(b, a, d).max(EditorSegments::distance(c, a, d));
+        if flat <= FLATTEN_TOLERANCE || depth >= FLATTEN_DEPTH {
+            points.push(d);
+            return;
+        }
+        let ab = (a + b) / 2.0;
+        let bc = (b + c) / 2.0;
+        let cd = (c + d) / 2.0;
+        let abc = (ab + bc) / 2.0;
+        let bcd = (bc + cd) / 2.0;
+        let middle = (abc + bcd) / 2.0;
+        EditorSegments::cubic(a, ab, abc, middle, depth + 1, points);
+        EditorSegments::cubic(middle, bcd, cd, d, depth + 1, points);
+    }
+
+    // Distance from `point` to the segment between `a` and `b`.
+    fn distance(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+        let direction = b - a;
+        let length = direction.length_squared();
+        if length <= f32::EPSILON {
+            return point.distance(a);
+        }
+        let t = ((point - a).dot(direction) / length).clamp(0.0, 1.0);
+        point.distance(a + direction * t)
+    }
+
+    // Anchors and handles in the order they are edited in vertex mode.
+    pub fn points(start: Vec2, segments: &[EditorSegments]) -> Vec<Vec2> {
+        let mut points = vec![start];
+        for i in segments {
+            match *i {
+                EditorSegments::Line { point } => points.push(point),
+                EditorSegments::Quadratic { control, point } => {
+                    points.extend([control, point]);
+                }
+                EditorSegments::Cubic {
+                    control_a,
+                    control_b,
+                    point,
+                } => points.extend([control_a, control_b, point]),
+            }
+        }
+        points
+    }
+
+    // Lines from each anchor to its handles.
+    pub fn handles(start: Vec2, segments: &[EditorSegments]) -> Vec<(Vec2, Vec2)> {
+        let mut handles = Vec::new();
+        let mut from = start;
+        for i in segments {
+            match *i {
+                EditorSegments::Line { .. } => {}
+                EditorSegments::Quadratic { control, point } => {
+                    handles.extend([(from, control), (point, control)]);
+                }
+                EditorSegments::Cubic {
+                    control_a,
+                    control_b,
+                    point,
+                } => handles.extend([(from, control_a), (point, control_b)]),
+            }
+            from = i.point();
+        }
+        handles
+    }
+
+    fn role(segments: &[EditorSegments], index: usize) -> Option<EditorPathPoints> {
+        let mut roles = vec![EditorPathPoints::Anchor(0)];
+        for (i, segment) in segments.iter().enumerate() {
+            match segment {
+                EditorSegments::Line { .. } => {}
+                EditorSegments::Quadratic { .. } => roles.push(EditorPathPoints::Control(i)),
+                EditorSegments::Cubic { .. } => {
+                    roles.push(EditorPathPoints::Out(i));
+                    roles.push(EditorPathPoints::In(i + 1));
+                }
+            }
+            roles.push(EditorPathPoints::Anchor(i + 1));
+        }
+        roles.get(index).copied()
+    }
+
+    // Handle leading into node `node`, closed paths join the last node to the start.
+    fn handle_in(segments: &mut [EditorSegments], closed: bool, node: usize) -> Option<&mut Vec2> {
+        let node = if node == 0 && closed {
+            segments.len()
+        } else {
+            node
+        };
+        match segments.get_mut(node.checked_sub(1)?)? {
+            EditorSegments::Cubic { control_b, .. } => Some(control_b),
+            _ => None,
+        }
+    }
+
+    // Handle leaving node `node`.
+    fn handle_out(segments: &mut [EditorSegments], closed: bool, node: usize) -> Option<&mut Vec2> {
+        let node = if node == segments.len() && closed {
+            0
+        } else {
+            node
+        };
+        match segments.get_mut(node)? {
+            EditorSegments::Cubic { control_a, .. } => Some(control_a),
+            _ => None,
+        }
+    }
+
+    fn anchor(start: Vec2, segments: &[EditorSegments], node: usize) -> Vec2 {
+        match node {
+            0 => start,
+            _ => segments[node - 1].point(),
+        }
+    }
+
+    fn set_anchor(start: &mut Vec2, segments: &mut [EditorSegments], node: usize, position: Vec2) {
+        match node {
+            0 => *start = position,
+            _ => match &mut segments[node - 1] {
+                EditorSegments::Line { point }
+                | EditorSegments::Quadratic { point, .. }
+                | EditorSegments::Cubic { point, .. } => *point = position,
+            },
+        }
+    }
+
+    // Moves the point `index` from `points`. Anchors carry their handles along
+    // and a handle of a smooth anchor turns the opposite one with it.
+    pub fn set_point(
+        start: &mut Vec2,
+        segments: &mut [EditorSegments],
+        closed: bool,
+        index: usize,
+        position: Vec2,
+    ) {
+        let Some(role) = EditorSegments::role(segments, index) else {
+            return;
+        };
+        match role {
+            EditorPathPoints::Anchor(node) => {
+                let offset = position - EditorSegments::anchor(*start, segments, node);
+                let mut nodes = vec![node];
+                if closed && (node == 0 || node == segments.len()) {
+                    nodes = vec![0, segments.len()];
+                }
+                for node in nodes {
+                    let anchor = EditorSegments::anchor(*start, segments, node);
+                    EditorSegments::set_anchor(start, segments, node, anchor + offset);
+                }
+                if let Some(handle) = EditorSegments::handle_in(segments, closed, node) {
+                    *handle += offset;
+                }
+                if let Some(handle) = EditorSegments::handle_out(segments, closed, node) {
+                    *handle += offset;
+                }
+            }
+            EditorPathPoints::In(node) | EditorPathPoints::Out(node) => {
+                let anchor = EditorSegments::anchor(*start, segments, node);
+                let into = matches!(role, EditorPathPoints::In(_));
+                let previous = if into {
+                    EditorSegments::handle_in(segments, closed, node).map(|i| *i)
+                } else {
+                    EditorSegments::handle_out(segments, closed, node).map(|i| *i)
+                };
+                let opposite = if into {
+                    EditorSegments::handle_out(segments, closed, node)
+                } else {
+                    EditorSegments::handle_in(segments, closed, node)
+                };
+                if let (Some(previous), Some(opposite)) = (previous, opposite) {
+                    let a = previous - anchor;
+                    let b = *opposite - anchor;
+                    // Collinear handles on either side mark a smooth anchor.
+                    let smooth =
+                        a.perp_dot(b).abs() <= 0.01 * a.length() * b.length() && a.dot(b) < 0.0;
+                    let direction = (anchor - position).normalize_or_zero();
+                    if smooth && direction != Vec2::ZERO {
+                        *opposite = anchor + direction * b.length();
+                    }
+                }
+                let handle = if into {
+                    EditorSegments::handle_in(segments, closed, node)
+                } else {
+                    EditorSegments::handle_out(segments, closed, node)
+                };
+                if let Some(handle) = handle {
+                    *handle = position;
+                }
+            }
+            EditorPathPoints::Control(segment) => {
+                if let EditorSegments::Quadratic { control, .. } = &mut segments[segment] {
+                    *control = position;
+                }
+            }
+        }
+    }
+
+    // Cubic curves along an SVG elliptical arc from `from` to `to`, at most a
+    // quarter turn each. Radii too small to reach `to` are scaled up.
+    pub fn arc(
+        from: Vec2,
+        to: Vec2,
+        radii: Vec2,
+        rotation: f32,
+        large: bool,
+        sweep: bool,
+    ) -> Vec<EditorSegments> {
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+        if from == to {
+            return Vec::new();
+        }
+        if rx <= f32::EPSILON || ry <= f32::EPSILON {
+            return vec![EditorSegments::Line { point: to }];
+        }
+        // Center parameterization from the SVG implementation notes.
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let rotate = |p: Vec2| Vec2::new(cos * p.x - sin * p.y, sin * p.x + cos * p.y);
+        let half = (from - to) / 2.0;
+        let p = Vec2::new(cos * half.x + sin * half.y, cos * half.y - sin * half.x);
+        let scale = (p.x / rx).powi(2) + (p.y / ry).powi(2);
+        if scale > 1.0 {
+            rx *= scale.sqrt();
+            ry *= scale.sqrt();
+        }
+        let a = (rx * p.y).powi(2) + (ry * p.x).powi(2);
+        let mut k = ((rx * ry).powi(2) / a - 1.0).max(0.0).sqrt();
+        if large == sweep {
+            k = -k;
+        }
+        let c = Vec2::new(k * rx * p.y / ry, -k * ry * p.x / rx);
+        let center = rotate(c) + (from + to) / 2.0;
+
+        let u = Vec2::new((p.x - c.x) / rx, (p.y - c.y) / ry);
+        let v = Vec2::new((-p.x - c.x) / rx, (-p.y - c.y) / ry);
+        let start = u.y.atan2(u.x);
+        let mut delta = v.y.atan2(v.x) - start;
+        if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        } else if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        }
+
+        let count = (delta.abs() / FRAC_PI_2 - 0.001).ceil().max(1.0) as usize;
+        let step = delta / count as f32;
+        let handle = 4.0 / 3.0 * (step / 4.0).tan();
+        let point = |angle: f32| center + rotate(Vec2::new(rx * angle.cos(), ry * angle.sin()));
+        let tangent = |angle: f32| rotate(Vec2::new(-rx * angle.sin(), ry * angle.cos()));
+        (0..count)
+            .map(|i| {
+                let a = start + step * i as f32;
+                let b = a + step;
+                let end = if i + 1 == count { to } else { point(b) };
+                EditorSegments::Cubic {
+                    control_a: point(a) + tangent(a) * handle,
+                    control_b: end - tangent(b) * handle,
+                    point: end,
+                }
+            })
+            .collect()
+    }
+
+    // Path data for the `d` attribute of an SVG path.
+    pub fn svg(start: Vec2, segments: &[EditorSegments], closed: bool) -> String {
+        let mut data = format!("M{:.1},{:.1}", start.x, start.y);
+        for i in segments {
+            match *i {
+                EditorSegments::Line { point } => {
+                    data.push_str(&format!(" L{:.1},{:.1}", point.x, point.y));
+                }
+                EditorSegments::Quadratic { control, point } => data.push_str(&format!(
+                    " Q{:.1},{:.1} {:.1},{:.1}",
+                    control.x, control.y, point.x, point.y
+                )),
+                EditorSegments::Cubic {
+                    control_a,
+                    control_b,
+                    point,
+                } => data.push_str(&format!(
+                    " C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                    control_a.x, control_a.y, control_b.x, control_b.y, point.x, point.y
+                )),
+            }
+        }
+        if closed {
+            data.push_str(" Z");
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Vec2;
+
+    use super::EditorSegments;
+    use super::FLATTEN_TOLERANCE;
+
+    #[test]
+    fn flattens_curves_within_tolerance() {
+        let start = Vec2::new(0.0, 0.0);
+        let segments = [EditorSegments::Quadratic {
+            control: Vec2::new(50.0, 100.0),
+            point: Vec2::new(100.0, 0.0),
+        }];
+        let points = EditorSegments::flatten(start, &segments);
+        assert!(points.len() > 8);
+        assert_eq!(points.first(), Some(&start));
+        assert_eq!(points.last(), Some(&Vec2::new(100.0, 0.0)));
+
+        // The apex of this curve is at t = 0.5, half way to the control.
+        let apex = Vec2::new(50.0, 50.0);
+        let closest = points
+            .windows(2)
+            .map(|i| EditorSegments::distance(apex, i[0], i[1]))
+            .fold(f32::INFINITY, f32::min);
+        assert!(closest <= FLATTEN_TOLERANCE);
+
+        let segments = [EditorSegments::Line {
+            point: Vec2::new(10.0, 0.0),
+        }];
+        assert_eq!(EditorSegments::flatten(start, &segments).len(), 2);
+    }
+
+    #[test]
+    fn smooth_handles_mirror_and_anchors_carry_them() {
+        let mut start = Vec2::new(0.0, 0.0);
+        let mut segments = [
+            EditorSegments::Cubic {
+                control_a: Vec2::new(0.0, -10.0),
+                control_b: Vec2::new(40.0, -10.0),
+                point: Vec2::new(50.0, 0.0),
+            },
+            EditorSegments::Cubic {
+                control_a: Vec2::new(60.0, 10.0),
+                control_b: Vec2::new(100.0, 10.0),
+                point: Vec2::new(100.0, 0.0),
+            },
+        ];
+
+        // Index 2 is the handle into the middle anchor.
+        EditorSegments::set_point(&mut start, &mut segments, false, 2, Vec2::new(50.0, -20.0));
+        let EditorSegments::Cubic { control_a, .. } = segments[1] else {
+            panic!("segment changed its kind");
+        };
+        assert!(control_a.distance(Vec2::new(50.0, 2.0_f32.sqrt() * 10.0)) < 0.01);
+
+        // Index 3 is the middle anchor itself.
+        EditorSegments::set_point(&mut start, &mut segments, false, 3, Vec2::new(50.0, 10.0));
+        assert_eq!(
+            EditorSegments::points(start, &segments)[2..5],
+            [
+                Vec2::new(50.0, -10.0),
+                Vec2::new(50.0, 10.0),
+                control_a + Vec2::new(0.0, 10.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,554 @@
+use macroquad::prelude::draw_circle_lines;
+use macroquad::prelude::draw_line;
+use macroquad::prelude::draw_mesh;
+use macroquad::prelude::draw_rectangle;
+use macroquad::prelude::draw_rectangle_lines;
+use macroquad::prelude::draw_text;
+use macroquad::prelude::is_key_down;
+use macroquad::prelude::is_key_pressed;
+use macroquad::prelude::is_mouse_button_down;
+use macroquad::prelude::is_mouse_button_pressed;
+use macroquad::prelude::is_mouse_button_released;
+use macroquad::prelude::measure_text;
+use macroquad::prelude::mouse_position;
+use macroquad::prelude::screen_height;
+use macroquad::prelude::screen_width;
+use macroquad::prelude::Color;
+use macroquad::prelude::KeyCode;
+use macroquad::prelude::Mesh;
+use macroquad::prelude::MouseButton;
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use macroquad::prelude::Vertex;
+use macroquad::prelude::BLACK;
+use macroquad::prelude::DARKGRAY;
+use macroquad::prelude::GRAY;
+use macroquad::prelude::GREEN;
+use macroquad::prelude::LIGHTGRAY;
+use macroquad::prelude::WHITE;
+use macroquad::prelude::YELLOW;
+
+use super::EditorElement;
+use super::EditorPrompt;
+use super::EditorPrompts;
+use super::EditorState;
+use super::SIZE_POINT;
+
+// Side of the saturation and value square.
+pub const PICKER_SIZE: f32 = 150.0;
+// Thickness of the sliders and size of the swatches.
+pub const PICKER_BAR: f32 = 14.0;
+// Recent colors kept with the document.
+pub const PICKER_RECENT: usize = 9;
+const PICKER_PADDING: f32 = 10.0;
+const PICKER_GAP: f32 = 8.0;
+const PICKER_TEXT: f32 = 16.0;
+// Swatches per row of palette entries.
+const PICKER_COLUMNS: usize = 9;
+
+// Color of new shapes and the selection the picker edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorPickers {
+    Fill,
+    Stroke,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorPickerParts {
+    Fill,
+    Stroke,
+    Eyedropper,
+    Square,
+    Hue,
+    Alpha,
+    Hex,
+    Recent(usize),
+    // Entry of the document palette.
+    Swatch(usize),
+}
+
+// Color picker panel, the color is kept as HSV so the hue survives while
+// the saturation or value is zero.
+#[derive(Debug, Clone)]
+pub struct EditorPicker {
+    pub target: EditorPickers,
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    pub alpha: f32,
+    pub drag: Option<EditorPickerParts>,
+    // Stack before a drag, pushed to undo once it ends.
+    pub stack: Option<Vec<EditorElement>>,
+    // The next click on the canvas samples an element.
+    pub eyedropper: bool,
+}
+
+impl EditorPickers {
+    pub fn text(&self) -> &str {
+        match self {
+            EditorPickers::Fill => "FILL",
+            EditorPickers::Stroke => "STROKE",
+        }
+    }
+}
+
+impl EditorPickerParts {
+    fn text(&self) -> &str {
+        match self {
+            EditorPickerParts::Fill => EditorPickers::Fill.text(),
+            EditorPickerParts::Stroke => EditorPickers::Stroke.text(),
+            EditorPickerParts::Eyedropper => "PICK",
+            _ => "",
+        }
+    }
+
+    fn width(&self) -> f32 {
+        measure_text(self.text(), None, PICKER_TEXT as u16, 1.0).width
+    }
+
+    // `swatches` is the size of the palette, the panel grows with it.
+    pub fn rect(&self, swatches: usize) -> Rect {
+        let panel = EditorPicker::panel(swatches);
+        let x = panel.x + PICKER_PADDING;
+        let y = panel.y + PICKER_PADDING;
+        let width = panel.w - PICKER_PADDING * 2.0;
+        let top = y + PICKER_TEXT + PICKER_GAP;
+        let row = |i: f32| top + PICKER_SIZE + PICKER_GAP + (PICKER_BAR + PICKER_GAP) * i;
+        match *self {
+            EditorPickerParts::Fill => Rect::new(x, y, self.width(), PICKER_TEXT),
+            EditorPickerParts::Stroke => {
+                let x = x + EditorPickerParts::Fill.width() + PICKER_PADDING;
+                Rect::new(x, y, self.width(), PICKER_TEXT)
+            }
+            EditorPickerParts::Eyedropper => {
+                Rect::new(x + width - self.width(), y, self.width(), PICKER_TEXT)
+            }
+            EditorPickerParts::Square => Rect::new(x, top, PICKER_SIZE, PICKER_SIZE),
+            EditorPickerParts::Hue => {
+                Rect::new(x + PICKER_SIZE + PICKER_GAP, top, PICKER_BAR, PICKER_SIZE)
+            }
+            EditorPickerParts::Alpha => Rect::new(x, row(0.0), width, PICKER_BAR),
+            EditorPickerParts::Hex => Rect::new(x, row(1.0), width, PICKER_BAR),
+            EditorPickerParts::Recent(i) => {
+                let x = x + (PICKER_BAR + PICKER_GAP / 2.0) * i as f32;
+                Rect::new(x, row(2.0), PICKER_BAR, PICKER_BAR)
+            }
+            EditorPickerParts::Swatch(i) => {
+                let column = (i % PICKER_COLUMNS) as f32;
+                let x = x + (PICKER_BAR + PICKER_GAP / 2.0) * column;
+                let y = row(3.0) + (PICKER_BAR + PICKER_GAP / 2.0) * (i / PICKER_COLUMNS) as f32;
+                Rect::new(x, y, PICKER_BAR, PICKER_BAR)
+            }
+        }
+    }
+
+    pub fn find(position: Vec2, recent: usize, swatches: usize) -> Option<Self> {
+        [
+            EditorPickerParts::Fill,
+            EditorPickerParts::Stroke,
+            EditorPickerParts::Eyedropper,
+            EditorPickerParts::Square,
+            EditorPickerParts::Hue,
+            EditorPickerParts::Alpha,
+            EditorPickerParts::Hex,
+        ]
+        .into_iter()
+        .chain((0..recent).map(EditorPickerParts::Recent))
+        .chain((0..swatches).map(EditorPickerParts::Swatch))
+        .find(|i| i.rect(swatches).contains(position))
+    }
+}
+
+impl EditorPicker {
+    pub fn new(target: EditorPickers, color: Color) -> Self {
+        let (hue, saturation, value) = EditorPicker::hsv(color);
+        Self {
+            target,
+            hue,
+            saturation,
+            value,
+            alpha: color.a,
+            drag: None,
+            stack: None,
+            eyedropper: false,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        EditorPicker::rgb(self.hue, self.saturation, self.value).with_alpha(self.alpha)
+    }
+
+    // Hue in degrees, saturation and value from 0 to 1.
+    pub fn hsv(color: Color) -> (f32, f32, f32) {
+        let max = color.r.max(color.g).max(color.b);
+        let min = color.r.min(color.g).min(color.b);
+        let delta = max - min;
+        let hue = if delta <= f32::EPSILON {
+            0.0
+        } else if max == color.r {
+            60.0 * ((color.g - color.b) / delta).rem_euclid(6.0)
+        } else if max == color.g {
+            60.0 * ((color.b - color.r) / delta + 2.0)
+        } else {
+            60.0 * ((color.r - color.g) / delta + 4.0)
+        };
+        let saturation = if max <= f32::EPSILON {
+            0.0
+        } else {
+            delta / max
+        };
+        (hue, saturation, max)
+    }
+
+    pub fn rgb(hue: f32, saturation: f32, value: f32) -> Color {
+        let channel = |n: f32| {
+            let k = (n + hue / 60.0).rem_euclid(6.0);
+            value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Color::new(channel(5.0), channel(3.0), channel(1.0), 1.0)
+    }
+
+    // `#rrggbbaa`, read back by `EditorSvg::parse_color`.
+    pub fn hex(color: Color) -> String {
+        let channel = |i: f32| (i.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            channel(color.a)
+        )
+    }
+
+    // Panel in screen space, above the style buttons.
+    pub fn panel(swatches: usize) -> Rect {
+        let width = PICKER_PADDING * 2.0 + PICKER_SIZE + PICKER_GAP + PICKER_BAR;
+        let rows = swatches.div_ceil(PICKER_COLUMNS) as f32;
+        let palette = if rows > 0.0 {
+            PICKER_GAP + PICKER_BAR * rows + PICKER_GAP / 2.0 * (rows - 1.0)
+        } else {
+            0.0
+        };
+        let height = PICKER_PADDING * 2.0
+            + PICKER_TEXT
+            + PICKER_GAP
+            + PICKER_SIZE
+            + (PICKER_GAP + PICKER_BAR) * 3.0
+            + palette;
+        Rect::new(
+            screen_width() - width - 10.0,
+            screen_height() - height - 40.0,
+            width,
+            height,
+        )
+    }
+
+    // Whether the pointer belongs to the picker instead of the canvas.
+    pub fn blocks(state: &EditorState) -> bool {
+        let position: Vec2 = mouse_position().into();
+        state.picker.as_ref().is_some_and(|i| {
+            let panel = EditorPicker::panel(state.palette.swatches.len());
+            i.eyedropper || i.drag.is_some() || panel.contains(position)
+        })
+    }
+
+    // Topmost element color under the canvas point `position`, the color of
+    // the picked part first.
+    fn sample(state: &EditorState, target: EditorPickers, position: Vec2) -> Option<Color> {
+        let unit = state.camera.unit();
+        let element = state
+            .stack
+            .iter()
+            .rev()
+            .find(|i| state.visible(i) && i.hit(position, SIZE_POINT * unit))?;
+        match target {
+            EditorPickers::Fill => element.fill.or(element.stroke),
+            EditorPickers::Stroke => element.stroke.or(element.fill),
+        }
+    }
+
+    // Quad with a color per corner, top left first and clockwise.
+    fn quad(rect: Rect, colors: [Color; 4]) {
+        let corners = [
+            (rect.x, rect.y),
+            (rect.right(), rect.y),
+            (rect.right(), rect.bottom()),
+            (rect.x, rect.bottom()),
+        ];
+        let vertices = corners
+            .iter()
+            .zip(colors)
+            .map(|((x, y), color)| Vertex::new(*x, *y, 0.0, 0.0, 0.0, color))
+            .collect();
+        draw_mesh(&Mesh {
+            vertices,
+            indices: vec![0, 1, 2, 0, 2, 3],
+            texture: None,
+        });
+    }
+
+    fn checkers(rect: Rect) {
+        let size = PICKER_BAR / 2.0;
+        let mut x = rect.x;
+        let mut column = 0;
+        while x < rect.right() {
+            for row in 0..2 {
+                let color = if (column + row) % 2 == 0 {
+                    GRAY
+                } else {
+                    DARKGRAY
+                };
+                let width = size.min(rect.right() - x);
+                draw_rectangle(x, rect.y + size * row as f32, width, size, color);
+            }
+            x += size;
+            column += 1;
+        }
+    }
+
+    pub fn draw(state: &mut EditorState) {
+        let Some(picker) = &state.picker else {
+            return;
+        };
+        let position: Vec2 = mouse_position().into();
+        let swatches = state.palette.swatches.len();
+        let hover = EditorPickerParts::find(position, state.recent.len(), swatches);
+        let panel = EditorPicker::panel(swatches);
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, BLACK.with_alpha(0.8));
+
+        for (part, active) in [
+            (
+                EditorPickerParts::Fill,
+                picker.target == EditorPickers::Fill,
+            ),
+            (
+                EditorPickerParts::Stroke,
+                picker.target == EditorPickers::Stroke,
+            ),
+            (EditorPickerParts::Eyedropper, picker.eyedropper),
+        ] {
+            let rect = part.rect(swatches);
+            let color = if active {
+                GREEN
+            } else if hover == Some(part) {
+                LIGHTGRAY
+            } else {
+                GRAY
+            };
+            draw_text(part.text(), rect.x, rect.bottom(), PICKER_TEXT, color);
+        }
+
+        // Columns keep the interpolation across the square close to HSV.
+        let square = EditorPickerParts::Square.rect(swatches);
+        let columns = 32;
+        for i in 0..columns {
+            let from = i as f32 / columns as f32;
+            let to = (i + 1) as f32 / columns as f32;
+            let rect = Rect::new(
+                square.x + square.w * from,
+                square.y,
+                square.w / columns as f32,
+                square.h,
+            );
+            let top_left = EditorPicker::rgb(picker.hue, from, 1.0);
+            let top_right = EditorPicker::rgb(picker.hue, to, 1.0);
+            EditorPicker::quad(rect, [top_left, top_right, BLACK, BLACK]);
+        }
+        let marker = Vec2::new(
+            square.x + square.w * picker.saturation,
+            square.y + square.h * (1.0 - picker.value),
+        );
+        let contrast = if picker.value > 0.5 { BLACK } else { WHITE };
+        draw_circle_lines(marker.x, marker.y, 4.0, 1.0, contrast);
+
+        let hue = EditorPickerParts::Hue.rect(swatches);
+        for i in 0..6 {
+            let rect = Rect::new(hue.x, hue.y + hue.h * i as f32 / 6.0, hue.w, hue.h / 6.0);
+            let top = EditorPicker::rgb(i as f32 * 60.0, 1.0, 1.0);
+            let bottom = EditorPicker::rgb((i + 1) as f32 * 60.0, 1.0, 1.0);
+            EditorPicker::quad(rect, [top, top, bottom, bottom]);
+        }
+        let y = hue.y + hue.h * picker.hue / 360.0;
+        draw_line(hue.x - 2.0, y, hue.right() + 2.0, y, 2.0, WHITE);
+
+        let alpha = EditorPickerParts::Alpha.rect(swatches);
+        let color = picker.color();
+        EditorPicker::checkers(alpha);
+        let clear = color.with_alpha(0.0);
+        let solid = color.with_alpha(1.0);
+        EditorPicker::quad(alpha, [clear, solid, solid, clear]);
+        let x = alpha.x + alpha.w * picker.alpha;
+        draw_line(x, alpha.y - 2.0, x, alpha.bottom() + 2.0, 2.0, WHITE);
+
+        let hex = EditorPickerParts::Hex.rect(swatches);
+        let swatch = Rect::new(hex.x, hex.y, PICKER_BAR * 2.0, hex.h);
+        EditorPicker::checkers(swatch);
+        draw_rectangle(swatch.x, swatch.y, swatch.w, swatch.h, color);
+        let text_color = if hover == Some(EditorPickerParts::Hex) {
+            YELLOW
+        } else {
+            LIGHTGRAY
+        };
+        // Palette entries show their name while hovered.
+        let text = match hover {
+            Some(EditorPickerParts::Swatch(i)) => state.palette.swatches[i].name.clone(),
+            _ => EditorPicker::hex(color),
+        };
+        let x = swatch.right() + PICKER_GAP;
+        draw_text(&text, x, hex.bottom() - 1.0, PICKER_TEXT, text_color);
+
+        for (i, color) in state.recent.iter().enumerate() {
+            let rect = EditorPickerParts::Recent(i).rect(swatches);
+            EditorPicker::checkers(rect);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color);
+            if hover == Some(EditorPickerParts::Recent(i)) {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, WHITE);
+            }
+        }
+
+        let current = state.swatch(picker.target);
+        for (i, swatch) in state.palette.swatches.iter().enumerate() {
+            let rect = EditorPickerParts::Swatch(i).rect(swatches);
+            EditorPicker::checkers(rect);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, swatch.color);
+            if hover == Some(EditorPickerParts::Swatch(i)) {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, WHITE);
+            } else if current == Some(swatch.name.as_str()) {
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, GREEN);
+            }
+        }
+
+        // The eyedropper previews the color it would take next to the cursor.
+        if picker.eyedropper && !panel.contains(position) {
+            let world = state.camera.world(position);
+            if let Some(color) = EditorPicker::sample(state, picker.target, world) {
+                let (x, y) = (position.x + 12.0, position.y + 12.0);
+                draw_rectangle(x, y, PICKER_BAR, PICKER_BAR, color);
+                draw_rectangle_lines(x, y, PICKER_BAR, PICKER_BAR, 1.0, WHITE);
+            }
+        }
+    }
+
+    pub fn actions(state: &mut EditorState) {
+        if state.prompt.is_some() {
+            return;
+        }
+        let Some(mut picker) = state.picker.take() else {
+            return;
+        };
+        let position: Vec2 = mouse_position().into();
+
+        if is_key_pressed(KeyCode::Escape) {
+            picker.eyedropper = false;
+        }
+        let swatches = state.palette.swatches.len();
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let part = EditorPickerParts::find(position, state.recent.len(), swatches);
+            if picker.eyedropper && part.is_none() {
+                picker.eyedropper = false;
+                let world = state.camera.world(position);
+                if let Some(color) = EditorPicker::sample(state, picker.target, world) {
+                    state.pick(picker.target, color);
+                    picker = EditorPicker::new(picker.target, color);
+                }
+            }
+            match part {
+                Some(EditorPickerParts::Fill) => {
+                    picker = EditorPicker::new(EditorPickers::Fill, state.element_color);
+                }
+                Some(EditorPickerParts::Stroke) => {
+                    let color = state.element_stroke_color;
+                    picker = EditorPicker::new(EditorPickers::Stroke, color);
+                }
+                Some(EditorPickerParts::Eyedropper) => {
+                    picker.eyedropper = !picker.eyedropper;
+                }
+                Some(
+                    part @ (EditorPickerParts::Square
+                    | EditorPickerParts::Hue
+                    | EditorPickerParts::Alpha),
+                ) => {
+                    picker.drag = Some(part);
+                    picker.stack = Some(state.stack.clone());
+                }
+                Some(EditorPickerParts::Hex) => {
+                    let text = EditorPicker::hex(picker.color());
+                    state.prompt = Some(EditorPrompt::new(EditorPrompts::Color, text));
+                }
+                Some(EditorPickerParts::Recent(i)) => {
+                    if let Some(color) = state.recent.get(i).copied() {
+                        state.pick(picker.target, color);
+                        picker = EditorPicker::new(picker.target, color);
+                    }
+                }
+                // Shift stores the picker color in the entry, alt removes it.
+                Some(EditorPickerParts::Swatch(i)) => {
+                    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                    let alt = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+                    if alt {
+                        state.palette_remove(i);
+                    } else if shift {
+                        state.palette_set(i, picker.color());
+                    } else {
+                        state.palette_pick(picker.target, i);
+                        picker = EditorPicker::new(picker.target, state.color(picker.target));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if let Some(part) = picker
+            .drag
+            .filter(|_| is_mouse_button_down(MouseButton::Left))
+        {
+            let rect = part.rect(swatches);
+            let offset = ((position - rect.point()) / rect.size()).clamp(Vec2::ZERO, Vec2::ONE);
+            match part {
+                EditorPickerParts::Square => {
+                    picker.saturation = offset.x;
+                    picker.value = 1.0 - offset.y;
+                }
+                EditorPickerParts::Hue => picker.hue = offset.y * 360.0,
+                _ => picker.alpha = offset.x,
+            }
+            state.recolor(picker.target, picker.color());
+        }
+        if is_mouse_button_released(MouseButton::Left) && picker.drag.take().is_some() {
+            if let Some(stack) = picker.stack.take() {
+                state.save_from(stack);
+            }
+            state.remember(picker.color());
+        }
+        state.picker = Some(picker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Color;
+
+    use super::EditorPicker;
+    use crate::editor::EditorSvg;
+
+    #[test]
+    fn converts_between_rgb_hsv_and_hex() {
+        let color = Color::new(1.0, 0.5, 0.0, 1.0);
+        let (hue, saturation, value) = EditorPicker::hsv(color);
+        assert!((hue - 30.0).abs() < 0.01);
+        assert_eq!((saturation, value), (1.0, 1.0));
+        assert_eq!(EditorPicker::rgb(hue, saturation, value), color);
+
+        let color = Color::new(0.2, 0.4, 0.8, 0.5);
+        let (hue, saturation, value) = EditorPicker::hsv(color);
+        let back = EditorPicker::rgb(hue, saturation, value);
+        assert!((back.r - 0.2).abs() < 0.001 && (back.g - 0.4).abs() < 0.001);
+        assert!((back.b - 0.8).abs() < 0.001);
+
+        assert_eq!(EditorPicker::hex(color), "#3366cc80");
+        let parsed = EditorSvg::parse_color("#3366cc80").unwrap();
+        assert_eq!(EditorPicker::hex(parsed), "#3366cc80");
+        let parsed = EditorSvg::parse_color("rgba(51, 102, 204, 0.5)").unwrap();
+        assert_eq!(EditorPicker::hex(parsed), "#3366cc80");
+    }
+}
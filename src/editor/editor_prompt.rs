@@ -0,0 +1,170 @@
+use macroquad::prelude::draw_rectangle;
+use macroquad::prelude::draw_text;
+use macroquad::prelude::get_char_pressed;
+use macroquad::prelude::is_key_pressed;
+use macroquad::prelude::screen_height;
+use macroquad::prelude::screen_width;
+use macroquad::prelude::KeyCode;
+use macroquad::prelude::BLACK;
+use macroquad::prelude::GRAY;
+use macroquad::prelude::LIGHTGRAY;
+use macroquad::prelude::YELLOW;
+use std::path::PathBuf;
+
+use super::EditorPicker;
+use super::EditorPickers;
+use super::EditorState;
+use super::EditorSvg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorPrompts {
+    Open,
+    Save,
+    Import,
+    ExportName,
+    ExportModule,
+    ExportTarget,
+    ExportScale,
+    ExportBackground,
+    ExportPalette,
+    ArtboardAdd,
+    ArtboardEdit,
+    LayerName,
+    Color,
+    Thickness,
+    PaletteOpen,
+    PaletteSave,
+    PaletteAdd,
+}
+
+#[derive(Debug, Clone)]
+pub struct EditorPrompt {
+    pub prompt: EditorPrompts,
+    pub text: String,
+}
+
+impl EditorPrompts {
+    pub fn text(&self) -> &str {
+        match self {
+            EditorPrompts::Open => "OPEN",
+            EditorPrompts::Save => "SAVE AS",
+            EditorPrompts::Import => "IMPORT SVG",
+            EditorPrompts::ExportName => "FUNCTION",
+            EditorPrompts::ExportModule => "MODULE",
+            EditorPrompts::ExportTarget => "TARGET",
+            EditorPrompts::ExportScale => "PNG SCALE",
+            EditorPrompts::ExportBackground => "PNG BACKGROUND",
+            EditorPrompts::ExportPalette => "PALETTE MODULE",
+            EditorPrompts::ArtboardAdd => "NEW ARTBOARD",
+            EditorPrompts::ArtboardEdit => "ARTBOARD",
+            EditorPrompts::LayerName => "LAYER",
+            EditorPrompts::Color => "COLOR",
+            EditorPrompts::Thickness => "STROKE WIDTH",
+            EditorPrompts::PaletteOpen => "OPEN PALETTE",
+            EditorPrompts::PaletteSave => "SAVE PALETTE",
+            EditorPrompts::PaletteAdd => "NEW SWATCH",
+        }
+    }
+}
+
+impl EditorPrompt {
+    pub fn new(prompt: EditorPrompts, text: String) -> Self {
+        Self { prompt, text }
+    }
+
+    pub fn draw(state: &mut EditorState) {
+        if let Some(prompt) = &state.prompt {
+            let width = screen_width();
+            let height = screen_height();
+            let text_size = 20.0;
+            let padding = 20.0;
+            let y = height / 2.0;
+
+            draw_rectangle(0.0, y - 30.0, width, 60.0, BLACK.with_alpha(0.8));
+            draw_text(prompt.prompt.text(), padding, y, text_size, YELLOW);
+            draw_text(
+                &format!("{}_", prompt.text),
+                padding + 100.0,
+                y,
+                text_size,
+                LIGHTGRAY,
+            );
+            draw_text(
+                "[ENTER] Confirm  [ESC] Cancel",
+                padding + 100.0,
+                y + 20.0,
+                14.0,
+                GRAY,
+            );
+        }
+    }
+
+    pub fn actions(state: &mut EditorState) {
+        let Some(prompt) = state.prompt.as_mut() else {
+            return;
+        };
+
+        while let Some(char) = get_char_pressed() {
+            if !char.is_control() {
+                prompt.text.push(char);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            prompt.text.pop();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            state.prompt = None;
+            return;
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            if let Some(prompt) = state.prompt.take() {
+                let text = prompt.text.trim();
+                match prompt.prompt {
+                    EditorPrompts::ExportModule => {
+                        state.export.module = text.to_string();
+                    }
+                    EditorPrompts::ExportTarget => {
+                        state.export.target =
+                            Some(PathBuf::from(text)).filter(|_| !text.is_empty());
+                    }
+                    EditorPrompts::ExportBackground => {
+                        state.export.background = EditorSvg::parse_color(text);
+                    }
+                    EditorPrompts::ExportPalette => {
+                        state.export.palette = text.to_string();
+                    }
+                    _ if text.is_empty() => {}
+                    EditorPrompts::Open => state.open(PathBuf::from(text)),
+                    EditorPrompts::Save => state.write(PathBuf::from(text)),
+                    EditorPrompts::Import => state.import(PathBuf::from(text)),
+                    EditorPrompts::ExportName => {
+                        state.export.name = text.to_string();
+                    }
+                    EditorPrompts::ExportScale => match text.parse::<f32>() {
+                        Ok(scale) if scale > 0.0 => state.export_scale(scale),
+                        _ => eprintln!("Invalid PNG scale: {text}"),
+                    },
+                    EditorPrompts::ArtboardAdd => state.artboard_add(text),
+                    EditorPrompts::ArtboardEdit => state.artboard_edit(text),
+                    EditorPrompts::LayerName => state.layer_rename(text),
+                    EditorPrompts::Color => match EditorSvg::parse_color(text) {
+                        Some(color) => {
+                            let target = state.picker.as_ref().map(|i| i.target);
+                            let target = target.unwrap_or(EditorPickers::Fill);
+                            state.pick(target, color);
+                            state.picker = Some(EditorPicker::new(target, color));
+                        }
+                        None => eprintln!("Invalid color: {text}"),
+                    },
+                    EditorPrompts::Thickness => match text.parse::<f32>() {
+                        Ok(thickness) if thickness > 0.0 => state.thickness(thickness),
+                        _ => eprintln!("Invalid stroke width: {text}"),
+                    },
+                    EditorPrompts::PaletteOpen => state.palette_open(PathBuf::from(text)),
+                    EditorPrompts::PaletteSave => state.palette_write(PathBuf::from(text)),
+                    EditorPrompts::PaletteAdd => state.palette_add(text),
+                }
+            }
+        }
+    }
+}
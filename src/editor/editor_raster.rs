@@ -0,0 +1,255 @@
+use macroquad::prelude::Color;
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::EditorElement;
+
+pub const PNG_EXTENSION: &str = "png";
+
+// Subsamples per pixel along each axis used for anti-aliasing.
+const SAMPLES: usize = 4;
+
+// Largest image exported, 8192 by 8192 pixels.
+const PIXELS_MAX: u64 = 1 << 26;
+
+// Software renderer producing the same shapes as `EditorElement::draw`
+// without a GPU context, pixels are stored with premultiplied alpha.
+#[derive(Debug, Clone)]
+pub struct EditorRaster {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Color>,
+}
+
+impl EditorRaster {
+    pub fn new(width: u32, height: u32, background: Option<Color>) -> Self {
+        let pixel = background
+            .map(|i| Color::new(i.r * i.a, i.g * i.a, i.b * i.a, i.a))
+            .unwrap_or(Color::new(0.0, 0.0, 0.0, 0.0));
+        // `render` keeps the size under `PIXELS_MAX`.
+        let pixels = vec![pixel; width as usize * height as usize];
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Pixel size of `frame` exported at `scale`, images over `PIXELS_MAX`
+    // pixels are refused.
+    pub fn size(frame: Rect, scale: f32) -> Result<(u32, u32), Box<dyn Error>> {
+        let side = |i: f32| {
+            let i = (i * scale).ceil().max(1.0);
+            Some(i as u32).filter(|_| i.is_finite() && i <= u32::MAX as f32)
+        };
+        let fits = |w: u32, h: u32| {
+            (w as u64)
+                .checked_mul(h as u64)
+                .is_some_and(|i| i <= PIXELS_MAX)
+        };
+        match side(frame.w).zip(side(frame.h)) {
+            Some((w, h)) if fits(w, h) => Ok((w, h)),
+            _ => Err(format!(
+                "{} by {} at scale {} is over {} pixels",
+                frame.w, frame.h, scale, PIXELS_MAX
+            )
+            .into()),
+        }
+    }
+
+    pub fn render(
+        stack: &[EditorElement],
+        frame: Rect,
+        scale: f32,
+        background: Option<Color>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (width, height) = EditorRaster::size(frame, scale)?;
+        let mut raster = EditorRaster::new(width, height, background);
+        for i in stack {
+            raster.draw(i, frame.point(), scale);
+        }
+        Ok(raster)
+    }
+
+    // Fill first and stroke on top, like the editor draws them. Shapes are
+    // tessellated once here instead of for every sample.
+    pub fn draw(&mut self, element: &EditorElement, origin: Vec2, scale: f32) {
+        let bounds = element.bounds();
+        let value = &element.value;
+        if let Some(color) = element.fill {
+            let fills = value.fills();
+            self.cover(bounds, origin, scale, color, |i| {
+                fills.iter().any(|j| j.contains(i))
+            });
+        }
+        if let Some(color) = element.stroke {
+            let thickness = element.thickness;
+            let strokes = value.strokes();
+            self.cover(bounds, origin, scale, color, |i| {
+                strokes.iter().any(|j| j.stroke(i, thickness) <= 0.0)
+            });
+        }
+    }
+
+    // Blends `color` over the pixels inside `bounds` by the share of their
+    // samples `covers` accepts.
+    fn cover(
+        &mut self,
+        bounds: Rect,
+        origin: Vec2,
+        scale: f32,
+        color: Color,
+        covers: impl Fn(Vec2) -> bool,
+    ) {
+        // Outlines reach slightly past the bounds, so the area is padded.
+        let min = ((bounds.point() - origin) * scale - Vec2::splat(2.0)).floor();
+        let max = ((bounds.point() + bounds.size() - origin) * scale + Vec2::splat(2.0)).ceil();
+        let x_min = min.x.max(0.0) as u32;
+        let y_min = min.y.max(0.0) as u32;
+        let x_max = (max.x.max(0.0) as u32).min(self.width);
+        let y_max = (max.y.max(0.0) as u32).min(self.height);
+
+        let step = 1.0 / SAMPLES as f32;
+        for y in y_min..y_max {
+            for x in x_min..x_max {
+                let mut covered = 0;
+                for sy in 0..SAMPLES {
+                    for sx in 0..SAMPLES {
+                        let sample = Vec2::new(
+                            x as f32 + (sx as f32 + 0.5) * step,
+                            y as f32 + (sy as f32 + 0.5) * step,
+                        );
+                        if covers(origin + sample / scale) {
+                            covered += 1;
+                        }
+                    }
+                }
+                if covered == 0 {
+                    continue;
+                }
+                let alpha = color.a * covered as f32 / (SAMPLES * SAMPLES) as f32;
+                let pixel = &mut self.pixels[y as usize * self.width as usize + x as usize];
+                pixel.r = color.r * alpha + pixel.r * (1.0 - alpha);
+                pixel.g = color.g * alpha + pixel.g * (1.0 - alpha);
+                pixel.b = color.b * alpha + pixel.b * (1.0 - alpha);
+                pixel.a = alpha + pixel.a * (1.0 - alpha);
+            }
+        }
+    }
+
+    pub fn rgba(&self) -> Vec<u8> {
+        let channel = |i: f32| (i.clamp(0.0, 1.0) * 255.0).round() as u8;
+        self.pixels
+            .iter()
+            .flat_map(|i| {
+                if i.a <= f32::EPSILON {
+                    return [0, 0, 0, 0];
+                }
+                [
+                    channel(i.r / i.a),
+                    channel(i.g / i.a),
+                    channel(i.b / i.a),
+                    channel(i.a),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        writer
+            .write_image_data(&self.rgba())
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        writer
+            .finish()
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        Ok(bytes)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.encode()?)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Color;
+    use macroquad::prelude::Rect;
+    use macroquad::prelude::Vec2;
+    use macroquad::prelude::RED;
+
+    use super::EditorRaster;
+    use crate::editor::EditorElement;
+    use crate::editor::EditorValues;
+
+    fn pixel(raster: &EditorRaster, x: u32, y: u32) -> [u8; 4] {
+        let rgba = raster.rgba();
+        let i = (y as usize * raster.width as usize + x as usize) * 4;
+        [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]]
+    }
+
+    #[test]
+    fn renders_circle_with_transparent_background() {
+        let center = Vec2::new(10.0, 10.0);
+        let value = EditorValues::Circle {
+            center,
+            radius: 5.2,
+        };
+        let stack = [EditorElement::new(value, Some(RED), None, 1.0)];
+        let frame = Rect::new(0.0, 0.0, 20.0, 20.0);
+        let raster = EditorRaster::render(&stack, frame, 2.0, None).unwrap();
+
+        assert_eq!((raster.width, raster.height), (40, 40));
+        assert_eq!(pixel(&raster, 20, 20), [230, 41, 56, 255]);
+        assert_eq!(pixel(&raster, 1, 1), [0, 0, 0, 0]);
+        let edge = pixel(&raster, 30, 20)[3];
+        assert!(edge > 0 && edge < 255);
+    }
+
+    #[test]
+    fn renders_solid_background_and_encodes_png() {
+        let value = EditorValues::Rectangle {
+            point: Vec2::new(2.0, 2.0),
+            width: 4.0,
+            height: 4.0,
+            rotation: 0.0,
+        };
+        let stack = [EditorElement::new(
+            value,
+            Some(Color::new(1.0, 1.0, 1.0, 0.5)),
+            None,
+            1.0,
+        )];
+        let frame = Rect::new(0.0, 0.0, 8.0, 8.0);
+        let background = Some(Color::new(0.0, 0.0, 0.0, 1.0));
+        let raster = EditorRaster::render(&stack, frame, 1.0, background).unwrap();
+
+        assert_eq!(pixel(&raster, 0, 0), [0, 0, 0, 255]);
+        assert_eq!(pixel(&raster, 3, 3), [128, 128, 128, 255]);
+
+        let bytes = raster.encode().unwrap();
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (8, 8));
+    }
+
+    #[test]
+    fn refuses_sizes_over_the_pixel_limit() {
+        let frame = Rect::new(0.0, 0.0, 1000.0, 1000.0);
+        assert_eq!(EditorRaster::size(frame, 2.0).unwrap(), (2000, 2000));
+        assert!(EditorRaster::size(frame, 100.0).is_err());
+        assert!(EditorRaster::size(frame, f32::INFINITY).is_err());
+        assert!(EditorRaster::size(frame, 1e30).is_err());
+    }
+}
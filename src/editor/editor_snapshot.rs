@@ -0,0 +1,19 @@
+use super::EditorArtboard;
+use super::EditorElement;
+use super::EditorFrames;
+use super::EditorLayer;
+use super::EditorPalette;
+
+// What undo and redo go back to, layers and the palette are kept with the
+// elements that refer to them, and the export frame with the artboards it
+// points into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorSnapshot {
+    pub stack: Vec<EditorElement>,
+    pub layers: Vec<EditorLayer>,
+    pub layer: usize,
+    pub palette: EditorPalette,
+    pub artboards: Vec<EditorArtboard>,
+    pub artboard: usize,
+    pub frame: EditorFrames,
+}
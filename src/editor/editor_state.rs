@@ -1,20 +1,50 @@
+use macroquad::miniquad::window::clipboard_get;
+use macroquad::miniquad::window::clipboard_set;
 use macroquad::prelude::draw_line;
+use macroquad::prelude::is_key_down;
 use macroquad::prelude::mouse_position;
 use macroquad::prelude::screen_height;
 use macroquad::prelude::screen_width;
 use macroquad::prelude::Color;
-use macroquad::prelude::DrawRectangleParams;
+use macroquad::prelude::KeyCode;
+use macroquad::prelude::Rect;
 use macroquad::prelude::Vec2;
 use macroquad::prelude::WHITE;
 use macroquad::prelude::YELLOW;
+use std::path::PathBuf;
 
+use super::EditorArtboard;
+use super::EditorBounds;
 use super::EditorButtons;
+use super::EditorCamera;
+use super::EditorClipboard;
+use super::EditorDocument;
 use super::EditorElement;
 use super::EditorElements;
+use super::EditorExport;
+use super::EditorFrames;
+use super::EditorHandles;
+use super::EditorLayer;
+use super::EditorMarquees;
+use super::EditorOrders;
+use super::EditorPalette;
+use super::EditorPicker;
+use super::EditorPickers;
+use super::EditorPrompt;
+use super::EditorPrompts;
+use super::EditorRaster;
+use super::EditorSegments;
+use super::EditorSnapshot;
+use super::EditorSvg;
 use super::EditorValues;
-
-pub const DISPLAY_SIZE: Vec2 = Vec2::new(640.0, 480.0);
-pub const DISPLAY_SIZE_HD: Vec2 = Vec2::new(1280.0, 720.0);
+use super::DOCUMENT_EXTENSION;
+use super::DUPLICATE_OFFSET;
+use super::PALETTE_EXTENSION;
+use super::PICKER_RECENT;
+use super::PNG_EXTENSION;
+use super::SVG_EXTENSION;
+use super::THICKNESS_MAX;
+use super::THICKNESS_MIN;
 
 pub const STICKY: f32 = 10.0;
 pub const STICKY_ELEMENT: f32 = 5.0;
@@ -27,14 +57,25 @@ pub struct EditorState {
     // pub element: Option<EditorElement>,
     pub element: EditorElements,
     pub element_thickness: f32,
+    pub element_sides: u8,
+    pub element_star: Option<f32>,
     pub element_color: Color,
-    pub element_color_index: usize,
+    pub element_fill: bool,
+    pub element_stroke: bool,
+    pub element_stroke_color: Color,
+    // Palette entries of the colors above, `None` for plain colors.
+    pub element_fill_swatch: Option<String>,
+    pub element_stroke_swatch: Option<String>,
     // pub element_lines: bool,
     pub stack: Vec<EditorElement>,
-    pub stack_undo: Vec<Vec<EditorElement>>,
-    pub stack_redo: Vec<Vec<EditorElement>>,
+    pub stack_undo: Vec<EditorSnapshot>,
+    pub stack_redo: Vec<EditorSnapshot>,
 
     pub current: Option<Vec2>,
+    pub points: Vec<Vec2>,
+    pub segments: Vec<EditorSegments>,
+    pub tangent: Option<Vec2>,
+    pub sweep: f32,
 
     pub button: Option<EditorButtons>,
 
@@ -45,6 +86,35 @@ pub struct EditorState {
 
     pub drag: bool,
     pub drag_offset: Option<Vec2>,
+    pub drag_stack: Option<Vec<EditorElement>>,
+
+    pub selection: Vec<usize>,
+    pub clipboard: EditorClipboard,
+    pub handle: Option<(EditorHandles, EditorBounds)>,
+    pub direct: bool,
+    pub vertex: Option<(usize, usize)>,
+    pub marquee: Option<Vec2>,
+    pub marquee_mode: EditorMarquees,
+    pub camera: EditorCamera,
+
+    pub path: Option<PathBuf>,
+    pub prompt: Option<EditorPrompt>,
+    pub export: EditorExport,
+    pub artboards: Vec<EditorArtboard>,
+    // Artboard edited or removed by the artboard commands.
+    pub artboard: usize,
+    pub picker: Option<EditorPicker>,
+    // Colors picked last, newest first.
+    pub recent: Vec<Color>,
+    pub palette: EditorPalette,
+    pub palette_path: Option<PathBuf>,
+    // Layers from the bottom up, the stack is kept in their order.
+    pub layers: Vec<EditorLayer>,
+    // Layer new elements go into.
+    pub layer: usize,
+    pub layer_panel: bool,
+    // Layer being dragged to another place in the panel.
+    pub layer_drag: Option<usize>,
     // pub position_cursor: Option<Vec2>,
     // pub button:
     // pub thickness: f32,
@@ -69,10 +139,20 @@ impl EditorState {
             // element: None,
             element: EditorElements::Line,
             element_thickness: 1.0,
+            element_sides: 5,
+            element_star: None,
             element_color: WHITE.with_alpha(0.5),
-            element_color_index: 0,
+            element_fill: true,
+            element_stroke: false,
+            element_stroke_color: WHITE.with_alpha(0.5),
+            element_fill_swatch: None,
+            element_stroke_swatch: None,
             // element_lines: false,
             current: None,
+            points: Vec::new(),
+            segments: Vec::new(),
+            tangent: None,
+            sweep: 0.0,
             // select
             button: Some(EditorButtons::Line),
 
@@ -83,6 +163,30 @@ impl EditorState {
 
             drag: false,
             drag_offset: None,
+            drag_stack: None,
+
+            selection: Vec::new(),
+            clipboard: EditorClipboard::default(),
+            handle: None,
+            direct: false,
+            vertex: None,
+            marquee: None,
+            marquee_mode: EditorMarquees::Inside,
+            camera: EditorCamera::new(),
+
+            path: None,
+            prompt: None,
+            export: EditorExport::default(),
+            artboards: EditorArtboard::defaults(),
+            artboard: 0,
+            picker: None,
+            recent: Vec::new(),
+            palette: EditorPalette::default(),
+            palette_path: None,
+            layers: EditorLayer::defaults(),
+            layer: 0,
+            layer_panel: false,
+            layer_drag: None,
             // cursor: None,
             // current_start: None,
             // sticky_radius: 10.0,
@@ -98,240 +202,872 @@ impl EditorState {
         }
     }
 
+    pub fn snapshot(&self) -> EditorSnapshot {
+        EditorSnapshot {
+            stack: self.stack.clone(),
+            layers: self.layers.clone(),
+            layer: self.layer,
+            palette: self.palette.clone(),
+            artboards: self.artboards.clone(),
+            artboard: self.artboard,
+            frame: self.export.frame,
+        }
+    }
+
     pub fn save(&mut self) {
-        self.stack_undo.push(self.stack.clone());
+        self.stack_undo.push(self.snapshot());
         self.stack_redo.clear();
     }
 
+    // Saves the `stack` from before a drag when the drag changed it.
+    pub fn save_from(&mut self, stack: Vec<EditorElement>) {
+        if stack != self.stack {
+            let snapshot = EditorSnapshot {
+                stack,
+                ..self.snapshot()
+            };
+            self.stack_undo.push(snapshot);
+            self.stack_redo.clear();
+        }
+    }
+
+    // Showing and locking layers aren't undo steps, layers that still exist
+    // keep them.
+    fn restore(&mut self, mut snapshot: EditorSnapshot) {
+        for layer in snapshot.layers.iter_mut() {
+            if let Some(i) = self.layers.iter().find(|i| i.id == layer.id) {
+                layer.visible = i.visible;
+                layer.locked = i.locked;
+            }
+        }
+        self.stack = snapshot.stack;
+        self.layers = snapshot.layers;
+        self.layer = snapshot.layer;
+        self.layer_drag = None;
+        self.palette = snapshot.palette;
+        self.artboards = snapshot.artboards;
+        self.artboard = snapshot.artboard;
+        self.export.frame = snapshot.frame;
+        self.selection.clear();
+        self.arrange();
+        self.palette_sync();
+    }
+
     pub fn undo(&mut self) {
-        if let Some(stack) = self.stack_undo.pop() {
-            self.stack_redo.push(self.stack.clone());
-            self.stack = stack;
+        if let Some(snapshot) = self.stack_undo.pop() {
+            self.stack_redo.push(self.snapshot());
+            self.restore(snapshot);
         }
     }
 
     pub fn redo(&mut self) {
-        if let Some(stack) = self.stack_redo.pop() {
-            self.stack_undo.push(self.stack.clone());
-            self.stack = stack;
+        if let Some(snapshot) = self.stack_redo.pop() {
+            self.stack_undo.push(self.snapshot());
+            self.restore(snapshot);
         }
     }
 
-    pub fn export(&self) {
-        let mut content = String::new();
-
-        let mut min_x = f32::MAX;
-        let mut min_y = f32::MAX;
-        let mut max_x = f32::MIN;
-        let mut max_y = f32::MIN;
-        for i in &self.stack {
-            match i.value {
-                EditorValues::Circle { center, radius } => {
-                    min_x = min_x.min(center.x - radius);
-                    min_y = min_y.min(center.y - radius);
-                    max_x = max_x.max(center.x + radius);
-                    max_y = max_y.max(center.y + radius);
-                }
-                EditorValues::Hexagon { center, radius, .. } => {
-                    min_x = min_x.min(center.x - radius);
-                    min_y = min_y.min(center.y - radius);
-                    max_x = max_x.max(center.x + radius);
-                    max_y = max_y.max(center.y + radius);
-                }
-                EditorValues::Rectangle {
-                    point,
-                    width,
-                    height,
-                    rotation,
-                } => {
-                    let hw = width / 2.0;
-                    let hh = height / 2.0;
-                    let cos_r = rotation.cos();
-                    let sin_r = rotation.sin();
-
-                    let corners = [
-                        Vec2::new(-hw, -hh),
-                        Vec2::new(hw, -hh),
-                        Vec2::new(hw, hh),
-                        Vec2::new(-hw, hh),
-                    ];
-
-                    for corner in corners.iter() {
-                        let rotated = Vec2::new(
-                            corner.x * cos_r - corner.y * sin_r,
-                            corner.x * sin_r + corner.y * cos_r,
-                        );
-                        let world = point + rotated;
-
-                        min_x = min_x.min(world.x);
-                        min_y = min_y.min(world.y);
-                        max_x = max_x.max(world.x);
-                        max_y = max_y.max(world.y);
-                    }
-                }
-                _ => {}
+    // Whether the element is drawn, snapped to and exported.
+    pub fn visible(&self, element: &EditorElement) -> bool {
+        self.layers
+            .iter()
+            .find(|i| i.id == element.layer)
+            .is_none_or(|i| i.visible)
+    }
+
+    // Whether the element can be picked on the canvas.
+    pub fn editable(&self, element: &EditorElement) -> bool {
+        self.layers
+            .iter()
+            .find(|i| i.id == element.layer)
+            .is_none_or(|i| i.visible && !i.locked)
+    }
+
+    // Visible elements in drawing order.
+    pub fn shown(&self) -> Vec<EditorElement> {
+        self.stack
+            .iter()
+            .filter(|i| self.visible(i))
+            .cloned()
+            .collect()
+    }
+
+    // Sorts the stack by layer, keeping the order within each and the
+    // selection. Elements of missing layers go to the bottom one.
+    pub fn arrange(&mut self) {
+        if self.layers.is_empty() {
+            self.layers = EditorLayer::defaults();
+        }
+        self.layer = self.layer.min(self.layers.len() - 1);
+        let bottom = self.layers[0].id;
+        for i in self.stack.iter_mut() {
+            if !self.layers.iter().any(|layer| layer.id == i.layer) {
+                i.layer = bottom;
             }
         }
-        let width = max_x - min_x;
-        let height = max_y - min_y;
-
-        content.push_str("fn draw(x: f32, y: f32) {\n");
-        content.push_str(&format!(
-            "   draw_rectangle_lines(x + {:.1}, y + {:.1}, {:.1}, {:.1}, 1.2, {:?});\n",
-            0.0, 0.0, width, height, YELLOW
-        ));
-
-        for i in self.stack.iter() {
-            let color = i.color;
-            match i.value {
-                EditorValues::Circle { center, radius } => content.push_str(&format!(
-                    "   draw_circle(x + {:.1}, y + {:.1}, {:.1}, {:?});\n",
-                    center.x - min_x,
-                    center.y - min_y,
-                    radius,
-                    color,
-                )),
-                EditorValues::Rectangle {
-                    width,
-                    height,
-                    rotation,
-                    point,
-                } => {
-                    let params = DrawRectangleParams {
-                        offset: Vec2::new(0.0, 0.0),
-                        rotation,
-                        color,
-                    };
-                    content.push_str(&format!(
-                        "   draw_rectangle_ex(x + {:.1}, y + {:.1}, {:.1}, {:.1}, {:?});\n",
-                        point.x - min_x,
-                        point.y - min_y,
-                        width,
-                        height,
-                        params,
-                    ));
-                }
-                EditorValues::Hexagon {
-                    center,
-                    radius,
-                    vertical,
-                } => {
-                    content.push_str(&format!(
-                        "   draw_hexagon(x + {:.1}, y + {:.1}, {:.1}, 1.0, {:?}, {:?}, {:?});\n",
-                        center.x - min_x,
-                        center.y - min_y,
-                        radius,
-                        vertical,
-                        color,
-                        color,
-                    ));
+        let mut order: Vec<usize> = (0..self.stack.len()).collect();
+        order.sort_by_key(|i| EditorLayer::order(&self.layers, self.stack[*i].layer));
+        self.permute(&order);
+    }
+
+    // Rebuilds the stack from indices into it, the selection follows.
+    fn permute(&mut self, order: &[usize]) {
+        self.stack = order.iter().map(|i| self.stack[*i].clone()).collect();
+        self.selection = self
+            .selection
+            .iter()
+            .filter_map(|s| order.iter().position(|i| i == s))
+            .collect();
+    }
+
+    // Changes the draw order of the selection in one undo step.
+    pub fn order(&mut self, order: EditorOrders) {
+        let layers: Vec<usize> = self
+            .stack
+            .iter()
+            .map(|i| EditorLayer::order(&self.layers, i.layer))
+            .collect();
+        let order = order.order(&layers, &self.selection);
+        if order.iter().enumerate().all(|(a, b)| a == *b) {
+            return;
+        }
+        self.save();
+        self.permute(&order);
+    }
+
+    // Places a new element on top of the active layer.
+    pub fn add(&mut self, mut element: EditorElement) {
+        let layer = &self.layers[self.layer];
+        element.layer = layer.id;
+        let index = self
+            .stack
+            .iter()
+            .rposition(|i| EditorLayer::order(&self.layers, i.layer) <= self.layer)
+            .map_or(0, |i| i + 1);
+        self.stack.insert(index, element);
+        for i in self.selection.iter_mut() {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+    }
+
+    pub fn select(&mut self, marquee: Rect) {
+        let found: Vec<usize> = self
+            .stack
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| self.editable(i) && self.marquee_mode.matches(marquee, i.bounds()))
+            .map(|(index, _)| index)
+            .collect();
+
+        if is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt) {
+            self.selection.retain(|i| !found.contains(i));
+        } else if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+            for i in found {
+                if !self.selection.contains(&i) {
+                    self.selection.push(i);
                 }
-                _ => content.push_str(""),
             }
+        } else {
+            self.selection = found;
         }
-        content.push_str("}\n");
-        println!("\n{}", content);
     }
-    pub fn position(&mut self) -> Vec2 {
-        let position: Vec2 = mouse_position().into();
 
-        if self.snap {
-            let width = screen_width();
-            let height = screen_height();
+    pub fn delete(&mut self) {
+        if self.selection.is_empty() {
+            return;
+        }
+        self.save();
+        let mut index = 0;
+        self.stack.retain(|_| {
+            let keep = !self.selection.contains(&index);
+            index += 1;
+            keep
+        });
+        self.selection.clear();
+    }
 
-            let color = YELLOW.with_alpha(0.2);
+    // Selected elements in stack order.
+    fn selected(&self) -> Vec<EditorElement> {
+        let mut selection = self.selection.clone();
+        selection.sort_unstable();
+        selection
+            .iter()
+            .filter_map(|i| self.stack.get(*i))
+            .cloned()
+            .collect()
+    }
 
-            let mut position_snap = position;
+    // Pushes `stack` on top and selects it.
+    fn insert(&mut self, stack: Vec<EditorElement>) {
+        if stack.is_empty() {
+            return;
+        }
+        let count = stack.len();
+        self.save();
+        self.selection.clear();
+        for element in stack {
+            self.add(element);
+        }
+        let end = self
+            .stack
+            .iter()
+            .rposition(|i| i.layer == self.layers[self.layer].id)
+            .map_or(0, |i| i + 1);
+        self.selection = (end - count..end).collect();
+        self.button = Some(EditorButtons::Select);
+        self.draw = false;
+        self.current = None;
+    }
 
-            if self.grid > 0 {
-                let nearest_x = (position_snap.x / SIZE_GRID).round() * SIZE_GRID;
-                if (position_snap.x - nearest_x).abs() < STICKY {
-                    position_snap.x = nearest_x;
-                }
-                let nearest_y = (position_snap.y / SIZE_GRID).round() * SIZE_GRID;
-                if (position_snap.y - nearest_y).abs() < STICKY {
-                    position_snap.y = nearest_y;
+    pub fn copy(&mut self) {
+        let stack = self.selected();
+        if stack.is_empty() {
+            return;
+        }
+        self.clipboard = EditorClipboard::new(stack);
+        match self.clipboard.text() {
+            Ok(text) => clipboard_set(&text),
+            Err(e) => eprintln!("Failed to copy: {e}"),
+        }
+    }
+
+    pub fn cut(&mut self) {
+        self.copy();
+        self.delete();
+    }
+
+    // Pastes at the original position, or centered on `position`.
+    pub fn paste(&mut self, position: Option<Vec2>) {
+        // Elements copied in another window take precedence.
+        if let Some(text) = clipboard_get() {
+            if let Ok(clipboard) = EditorClipboard::parse(&text) {
+                self.clipboard = clipboard;
+            }
+        }
+        let stack = match position {
+            Some(position) => self.clipboard.at(position),
+            None => self.clipboard.stack.clone(),
+        };
+        self.insert(stack);
+    }
+
+    pub fn duplicate(&mut self) {
+        let mut stack = self.selected();
+        for i in stack.iter_mut() {
+            i.value.translate(DUPLICATE_OFFSET);
+        }
+        self.insert(stack);
+    }
+
+    // Zooms around the middle of the window.
+    pub fn zoom(&mut self, factor: f32) {
+        let center = Vec2::new(screen_width(), screen_height()) / 2.0;
+        self.camera.zoom_at(center, factor);
+    }
+
+    pub fn zoom_fit(&mut self) {
+        let bounds = EditorFrames::Content.rect(&self.stack, &[]);
+        self.camera.fit(bounds);
+    }
+
+    pub fn zoom_selection(&mut self) {
+        let bounds = self
+            .selected()
+            .iter()
+            .map(|i| i.bounds())
+            .reduce(|a, b| a.combine_with(b));
+        if let Some(bounds) = bounds {
+            self.camera.fit(bounds);
+        }
+    }
+
+    // Adds a layer above the active one and makes it active.
+    pub fn layer_add(&mut self) {
+        self.save();
+        let id = self.layers.iter().map(|i| i.id + 1).max().unwrap_or(0);
+        let name = (self.layers.len() + 1..)
+            .map(|i| format!("LAYER {}", i))
+            .find(|i| !self.layers.iter().any(|layer| layer.name == *i))
+            .unwrap_or_default();
+        self.layer = (self.layer + 1).min(self.layers.len());
+        self.layers.insert(self.layer, EditorLayer::new(id, &name));
+    }
+
+    // Removes the active layer with its elements, one layer always stays.
+    pub fn layer_remove(&mut self) {
+        if self.layers.len() < 2 || self.layer >= self.layers.len() {
+            return;
+        }
+        self.save();
+        let id = self.layers.remove(self.layer).id;
+        self.selection.clear();
+        self.stack.retain(|i| i.layer != id);
+        self.layer = self.layer.saturating_sub(1);
+    }
+
+    // Names stay unique so layers can be told apart in the panel.
+    pub fn layer_rename(&mut self, name: &str) {
+        let name = name.to_uppercase();
+        if self.layers.get(self.layer).is_none_or(|i| i.name == name) {
+            return;
+        }
+        if self.layers.iter().any(|i| i.name == name) {
+            eprintln!("Layer {name} already exists");
+            return;
+        }
+        self.save();
+        self.layers[self.layer].name = name;
+    }
+
+    // Hidden and locked layers drop out of the selection.
+    fn layer_deselect(&mut self) {
+        let selection = std::mem::take(&mut self.selection);
+        self.selection = selection
+            .into_iter()
+            .filter(|i| self.stack.get(*i).is_some_and(|i| self.editable(i)))
+            .collect();
+    }
+
+    pub fn layer_visible(&mut self, index: usize) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.visible = !layer.visible;
+        }
+        self.layer_deselect();
+    }
+
+    pub fn layer_lock(&mut self, index: usize) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.locked = !layer.locked;
+        }
+        self.layer_deselect();
+    }
+
+    // Moves the layer at `from` to `to`, its elements follow.
+    pub fn layer_reorder(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.layers.len() || to >= self.layers.len() {
+            return;
+        }
+        self.save();
+        let layer = self.layers.remove(from);
+        self.layers.insert(to, layer);
+        self.layer = to;
+        self.arrange();
+    }
+
+    // Moves the selection on top of the layer at `index` in one undo step.
+    pub fn layer_move(&mut self, index: usize) {
+        let Some(id) = self.layers.get(index).map(|i| i.id) else {
+            return;
+        };
+        if self.selection.is_empty() {
+            return;
+        }
+        self.save();
+        let mut selection = self.selection.clone();
+        selection.sort_unstable();
+        selection.dedup();
+        selection.retain(|i| *i < self.stack.len());
+        // Removed from the end so the indices stay valid.
+        let moved: Vec<EditorElement> = selection
+            .iter()
+            .rev()
+            .map(|i| self.stack.remove(*i))
+            .collect();
+        self.selection.clear();
+        self.layer = index;
+        for element in moved.iter().rev() {
+            self.add(element.clone());
+        }
+        let end = self
+            .stack
+            .iter()
+            .rposition(|i| i.layer == id)
+            .map_or(0, |i| i + 1);
+        self.selection = (end - moved.len()..end).collect();
+    }
+
+    pub fn artboard_next(&mut self) {
+        if !self.artboards.is_empty() {
+            self.artboard = (self.artboard + 1) % self.artboards.len();
+        }
+    }
+
+    // Adds an artboard from `NAME WIDTHxHEIGHT` right of the others.
+    pub fn artboard_add(&mut self, text: &str) {
+        let Some((name, size)) = EditorArtboard::parse(text) else {
+            eprintln!("Invalid artboard: {text}");
+            return;
+        };
+        let name = if name.is_empty() {
+            format!("ARTBOARD {}", self.artboards.len() + 1)
+        } else {
+            name
+        };
+        self.save();
+        let point = EditorArtboard::place(&self.artboards);
+        self.artboards.push(EditorArtboard::new(&name, point, size));
+        self.artboard = self.artboards.len() - 1;
+        self.camera.fit(self.artboards[self.artboard].rect());
+    }
+
+    // Renames and resizes the active artboard, its top left corner stays.
+    pub fn artboard_edit(&mut self, text: &str) {
+        if self.artboard >= self.artboards.len() {
+            return;
+        }
+        let Some((name, size)) = EditorArtboard::parse(text) else {
+            eprintln!("Invalid artboard: {text}");
+            return;
+        };
+        self.save();
+        let artboard = &mut self.artboards[self.artboard];
+        if !name.is_empty() {
+            artboard.name = name;
+        }
+        artboard.size = size;
+    }
+
+    pub fn artboard_remove(&mut self) {
+        if self.artboard >= self.artboards.len() {
+            return;
+        }
+        self.save();
+        self.artboards.remove(self.artboard);
+        self.export.frame = self.export.frame.remove(self.artboard);
+        self.artboard = self.artboard.min(self.artboards.len().saturating_sub(1));
+    }
+
+    // Fill and stroke colors for a shape. Open shapes have no fill, so they
+    // are stroked in the fill color while strokes are off.
+    pub fn paint(&self, closed: bool) -> (Option<Color>, Option<Color>) {
+        let fill = closed && (self.element_fill || !self.element_stroke);
+        let stroke = if self.element_stroke {
+            Some(self.element_stroke_color)
+        } else {
+            (!closed).then_some(self.element_color)
+        };
+        (fill.then_some(self.element_color), stroke)
+    }
+
+    // Palette entries behind the colors of `paint`.
+    pub fn swatches(&self, closed: bool) -> (Option<String>, Option<String>) {
+        let (fill, stroke) = self.paint(closed);
+        let stroke_swatch = if self.element_stroke {
+            &self.element_stroke_swatch
+        } else {
+            &self.element_fill_swatch
+        };
+        (
+            fill.and(self.element_fill_swatch.clone()),
+            stroke.and(stroke_swatch.clone()),
+        )
+    }
+
+    // New element with the current style.
+    pub fn style(&self, value: EditorValues) -> EditorElement {
+        let closed = value.closed();
+        let (fill, stroke) = self.paint(closed);
+        let (fill_swatch, stroke_swatch) = self.swatches(closed);
+        EditorElement {
+            fill_swatch,
+            stroke_swatch,
+            ..EditorElement::new(value, fill, stroke, self.element_thickness)
+        }
+    }
+
+    // Applies the current colors to the selection.
+    pub fn restyle(&mut self) {
+        if self.selection.is_empty() {
+            return;
+        }
+        self.save();
+        for i in self.selection.clone() {
+            let Some(closed) = self.stack.get(i).map(|i| i.value.closed()) else {
+                continue;
+            };
+            let (fill, stroke) = self.paint(closed);
+            let (fill_swatch, stroke_swatch) = self.swatches(closed);
+            if let Some(element) = self.stack.get_mut(i) {
+                element.fill = fill;
+                element.stroke = stroke;
+                element.fill_swatch = fill_swatch;
+                element.stroke_swatch = stroke_swatch;
+            }
+        }
+    }
+
+    // Sets the stroke width of new shapes and of the selection.
+    pub fn thickness(&mut self, thickness: f32) {
+        let thickness = thickness.clamp(THICKNESS_MIN, THICKNESS_MAX);
+        self.element_thickness = thickness;
+        self.export.guide = thickness;
+        if self
+            .selection
+            .iter()
+            .all(|i| self.stack.get(*i).is_none_or(|i| i.thickness == thickness))
+        {
+            return;
+        }
+        self.save();
+        for i in self.selection.iter() {
+            if let Some(element) = self.stack.get_mut(*i) {
+                element.thickness = thickness;
+            }
+        }
+    }
+
+    pub fn color(&self, target: EditorPickers) -> Color {
+        match target {
+            EditorPickers::Fill => self.element_color,
+            EditorPickers::Stroke => self.element_stroke_color,
+        }
+    }
+
+    // Sets the color of new shapes and of the selected fills or strokes.
+    pub fn recolor(&mut self, target: EditorPickers, color: Color) {
+        self.reference(target, color, None);
+    }
+
+    // Sets the color together with the palette entry it comes from.
+    fn reference(&mut self, target: EditorPickers, color: Color, swatch: Option<String>) {
+        match target {
+            EditorPickers::Fill => {
+                self.element_color = color;
+                self.element_fill_swatch = swatch.clone();
+            }
+            EditorPickers::Stroke => {
+                self.element_stroke_color = color;
+                self.element_stroke_swatch = swatch.clone();
+            }
+        }
+        for i in self.selection.iter() {
+            if let Some(element) = self.stack.get_mut(*i) {
+                let (paint, reference) = match target {
+                    EditorPickers::Fill => (&mut element.fill, &mut element.fill_swatch),
+                    EditorPickers::Stroke => (&mut element.stroke, &mut element.stroke_swatch),
+                };
+                if paint.is_some() {
+                    *paint = Some(color);
+                    *reference = swatch.clone();
                 }
             }
+        }
+    }
+
+    // Current palette entry of the fill or the stroke.
+    pub fn swatch(&self, target: EditorPickers) -> Option<&str> {
+        match target {
+            EditorPickers::Fill => self.element_fill_swatch.as_deref(),
+            EditorPickers::Stroke => self.element_stroke_swatch.as_deref(),
+        }
+    }
+
+    // Uses the palette entry at `index` in one undo step.
+    pub fn palette_pick(&mut self, target: EditorPickers, index: usize) {
+        let Some(swatch) = self.palette.swatches.get(index).cloned() else {
+            return;
+        };
+        if !self.selection.is_empty() {
+            self.save();
+        }
+        self.reference(target, swatch.color, Some(swatch.name));
+    }
 
-            let display = Vec2::new(
-                width / 2.0 - DISPLAY_SIZE.x / 2.0,
-                height / 2.0 - DISPLAY_SIZE.y / 2.0,
-            );
-            if (position.x - display.x).abs() < STICKY {
-                position_snap.x = display.x;
-                draw_line(position_snap.x, 0.0, position_snap.x, height, 1.0, color);
+    pub fn palette_open(&mut self, path: PathBuf) {
+        match EditorPalette::read(&path) {
+            Ok(palette) => {
+                println!(
+                    "Opened palette {} with {} colors",
+                    path.display(),
+                    palette.swatches.len()
+                );
+                self.save();
+                self.palette = palette;
+                self.palette_path = Some(path);
+                self.palette_sync();
             }
-            if (position.x - (display.x + DISPLAY_SIZE.x)).abs() < STICKY {
-                position_snap.x = display.x + DISPLAY_SIZE.x;
-                draw_line(position_snap.x, 0.0, position_snap.x, height, 1.0, color);
+            Err(e) => eprintln!("Failed to open palette: {e}"),
+        }
+    }
+
+    pub fn palette_write(&mut self, path: PathBuf) {
+        let path = if path.extension().is_none() {
+            path.with_extension(PALETTE_EXTENSION)
+        } else {
+            path
+        };
+        match self.palette.write(&path) {
+            Ok(()) => {
+                println!("Saved palette {}", path.display());
+                self.palette_path = Some(path);
             }
-            if (position.y - display.y).abs() < STICKY {
-                position_snap.y = display.y;
-                draw_line(0.0, position_snap.y, width, position_snap.y, 1.0, color);
+            Err(e) => eprintln!("Failed to save palette: {e}"),
+        }
+    }
+
+    // Adds the color of the picker, or the fill color, under `name`.
+    pub fn palette_add(&mut self, name: &str) {
+        let target = self.picker.as_ref().map(|i| i.target);
+        let target = target.unwrap_or(EditorPickers::Fill);
+        let color = self.color(target);
+        self.save();
+        self.palette.set(name, color);
+        self.palette_sync();
+        self.reference(target, color, Some(name.to_string()));
+    }
+
+    // Changes the color of an entry and of everything using it.
+    pub fn palette_set(&mut self, index: usize, color: Color) {
+        if index >= self.palette.swatches.len() {
+            return;
+        }
+        self.save();
+        self.palette.swatches[index].color = color;
+        self.palette_sync();
+    }
+
+    // Elements using the entry keep its color as a plain one.
+    pub fn palette_remove(&mut self, index: usize) {
+        if index >= self.palette.swatches.len() {
+            return;
+        }
+        self.save();
+        let name = self.palette.swatches.remove(index).name;
+        let name = Some(name);
+        for i in self.stack.iter_mut() {
+            if i.fill_swatch == name {
+                i.fill_swatch = None;
             }
-            if (position.y - (display.y + DISPLAY_SIZE.y)).abs() < STICKY {
-                position_snap.y = display.y + DISPLAY_SIZE.y;
-                draw_line(0.0, position_snap.y, width, position_snap.y, 1.0, color);
+            if i.stroke_swatch == name {
+                i.stroke_swatch = None;
             }
+        }
+        if self.element_fill_swatch == name {
+            self.element_fill_swatch = None;
+        }
+        if self.element_stroke_swatch == name {
+            self.element_stroke_swatch = None;
+        }
+    }
 
-            let display = Vec2::new(
-                width / 2.0 - DISPLAY_SIZE_HD.x / 2.0,
-                height / 2.0 - DISPLAY_SIZE_HD.y / 2.0,
-            );
-            if (position.x - display.x).abs() < STICKY {
-                position_snap.x = display.x;
-                draw_line(position_snap.x, 0.0, position_snap.x, height, 1.0, color);
+    // Applies the palette colors to the elements and the style using them,
+    // references to missing entries keep their last color.
+    pub fn palette_sync(&mut self) {
+        let palette = &self.palette;
+        let sync = |color: &mut Color, swatch: &Option<String>| {
+            if let Some(synced) = swatch.as_deref().and_then(|i| palette.color(i)) {
+                *color = synced;
             }
-            if (position.x - (display.x + DISPLAY_SIZE_HD.x)).abs() < STICKY {
-                position_snap.x = display.x + DISPLAY_SIZE_HD.x;
-                draw_line(position_snap.x, 0.0, position_snap.x, height, 1.0, color);
+        };
+        for i in self.stack.iter_mut() {
+            if let Some(fill) = i.fill.as_mut() {
+                sync(fill, &i.fill_swatch);
             }
-            if (position.y - display.y).abs() < STICKY {
-                position_snap.y = display.y;
-                draw_line(0.0, position_snap.y, width, position_snap.y, 1.0, color);
+            if let Some(stroke) = i.stroke.as_mut() {
+                sync(stroke, &i.stroke_swatch);
             }
-            if (position.y - (display.y + DISPLAY_SIZE_HD.y)).abs() < STICKY {
-                position_snap.y = display.y + DISPLAY_SIZE_HD.y;
-                draw_line(0.0, position_snap.y, width, position_snap.y, 1.0, color);
+        }
+        sync(&mut self.element_color, &self.element_fill_swatch);
+        sync(&mut self.element_stroke_color, &self.element_stroke_swatch);
+    }
+
+    // Recolors in one undo step and keeps the color with the recent ones.
+    pub fn pick(&mut self, target: EditorPickers, color: Color) {
+        if !self.selection.is_empty() {
+            self.save();
+        }
+        self.recolor(target, color);
+        self.remember(color);
+    }
+
+    pub fn remember(&mut self, color: Color) {
+        self.recent.retain(|i| *i != color);
+        self.recent.insert(0, color);
+        self.recent.truncate(PICKER_RECENT);
+    }
+
+    pub fn open(&mut self, path: PathBuf) {
+        match EditorDocument::read(&path) {
+            Ok(document) => {
+                document.apply(self);
+                println!("Opened {}", path.display());
+                self.path = Some(path);
             }
+            Err(e) => eprintln!("Failed to open document: {e}"),
+        }
+    }
 
-            if (position.x - 0.0).abs() < STICKY {
-                position_snap.x = 0.0;
+    pub fn write(&mut self, path: PathBuf) {
+        let path = if path.extension().is_none() {
+            path.with_extension(DOCUMENT_EXTENSION)
+        } else {
+            path
+        };
+        match EditorDocument::new(self).write(&path) {
+            Ok(()) => {
+                println!("Saved {}", path.display());
+                self.path = Some(path);
             }
-            if (position.x - width).abs() < STICKY {
-                position_snap.x = width;
+            Err(e) => eprintln!("Failed to save document: {e}"),
+        }
+    }
+
+    pub fn write_current(&mut self) {
+        match self.path.clone() {
+            Some(path) => self.write(path),
+            None => self.write_as(),
+        }
+    }
+
+    pub fn write_as(&mut self) {
+        let text = self
+            .path
+            .as_ref()
+            .map(|i| i.display().to_string())
+            .unwrap_or_default();
+        self.prompt = Some(EditorPrompt::new(EditorPrompts::Save, text));
+    }
+
+    pub fn export(&self) {
+        let path = self.export.path(self.path.as_deref());
+        let stack = self.shown();
+        match self
+            .export
+            .write(&path, &stack, &self.layers, &self.artboards, &self.palette)
+        {
+            Ok(()) => println!("Exported {} to {}", self.export.name, path.display()),
+            Err(e) => eprintln!("Failed to export: {e}"),
+        }
+    }
+
+    pub fn import(&mut self, path: PathBuf) {
+        match EditorSvg::read(&path) {
+            Ok(import) => {
+                for i in import.skipped.iter() {
+                    eprintln!("Skipped SVG element: {i}");
+                }
+                if !import.elements.is_empty() {
+                    self.save();
+                    for element in import.elements.iter() {
+                        self.add(element.clone());
+                    }
+                }
+                println!(
+                    "Imported {} elements from {}, skipped {}",
+                    import.elements.len(),
+                    path.display(),
+                    import.skipped.len()
+                );
             }
-            if (position.y - 0.0).abs() < STICKY {
-                position_snap.y = 0.0;
+            Err(e) => eprintln!("Failed to import SVG: {e}"),
+        }
+    }
+
+    pub fn export_svg(&self) {
+        let path = self.export.file(self.path.as_deref(), SVG_EXTENSION);
+        let stack = self.shown();
+        let frame = self.export.frame.rect(&stack, &self.artboards);
+        match EditorSvg::write(&path, &stack, frame) {
+            Ok(()) => println!("Exported SVG to {}", path.display()),
+            Err(e) => eprintln!("Failed to export SVG: {e}"),
+        }
+    }
+
+    pub fn export_png(&self) {
+        let path = self.export.file(self.path.as_deref(), PNG_EXTENSION);
+        let stack = self.shown();
+        let frame = self.export.frame.rect(&stack, &self.artboards);
+        let raster = EditorRaster::render(&stack, frame, self.export.scale, self.export.background);
+        match raster.and_then(|i| i.write(&path)) {
+            Ok(()) => println!("Exported PNG to {}", path.display()),
+            Err(e) => eprintln!("Failed to export PNG: {e}"),
+        }
+    }
+
+    // Scale of PNG exports, refused when the current frame would become
+    // too large an image.
+    pub fn export_scale(&mut self, scale: f32) {
+        let stack = self.shown();
+        let frame = self.export.frame.rect(&stack, &self.artboards);
+        match EditorRaster::size(frame, scale) {
+            Ok(_) => self.export.scale = scale,
+            Err(e) => eprintln!("Invalid PNG scale: {e}"),
+        }
+    }
+
+    pub fn position(&mut self) -> Vec2 {
+        let position = self.camera.world(mouse_position().into());
+
+        if self.snap {
+            // Snap distances and guides stay the same on screen at any zoom.
+            let unit = self.camera.unit();
+            let view = self.camera.view();
+
+            let color = YELLOW.with_alpha(0.2);
+
+            let mut position_snap = position;
+
+            if self.grid > 0 {
+                let nearest_x = (position_snap.x / SIZE_GRID).round() * SIZE_GRID;
+                if (position_snap.x - nearest_x).abs() < STICKY * unit {
+                    position_snap.x = nearest_x;
+                }
+                let nearest_y = (position_snap.y / SIZE_GRID).round() * SIZE_GRID;
+                if (position_snap.y - nearest_y).abs() < STICKY * unit {
+                    position_snap.y = nearest_y;
+                }
             }
-            if (position.y - height).abs() < STICKY {
-                position_snap.y = height;
+
+            for artboard in self.artboards.iter() {
+                let rect = artboard.rect();
+                for x in [rect.x, rect.right()] {
+                    if (position.x - x).abs() < STICKY * unit {
+                        position_snap.x = x;
+                        draw_line(x, view.y, x, view.bottom(), unit, color);
+                    }
+                }
+                for y in [rect.y, rect.bottom()] {
+                    if (position.y - y).abs() < STICKY * unit {
+                        position_snap.y = y;
+                        draw_line(view.x, y, view.right(), y, unit, color);
+                    }
+                }
             }
 
-            for i in self.stack.iter() {
+            for (index, i) in self.stack.iter().enumerate() {
+                // Points and selections being dragged would otherwise stick to
+                // themselves.
+                let moving = self.drag && self.selection.contains(&index);
+                if self.vertex.is_some_and(|(element, _)| element == index)
+                    || moving
+                    || !self.visible(i)
+                {
+                    continue;
+                }
                 match i.value {
                     EditorValues::Line {
                         point_a, point_b, ..
                     } => {
-                        if position.distance(point_a) <= STICKY_ELEMENT {
+                        if position.distance(point_a) <= STICKY_ELEMENT * unit {
                             position_snap = point_a;
                         }
-                        if position.distance(point_b) <= STICKY_ELEMENT {
+                        if position.distance(point_b) <= STICKY_ELEMENT * unit {
                             position_snap = point_b;
                         }
 
-                        if (position.x - point_a.x).abs() < SIZE_POINT {
+                        if (position.x - point_a.x).abs() < SIZE_POINT * unit {
                             position_snap.x = point_a.x;
                         }
-                        if (position.y - point_a.y).abs() < SIZE_POINT {
+                        if (position.y - point_a.y).abs() < SIZE_POINT * unit {
                             position_snap.y = point_a.y;
                         }
 
-                        if (position.x - point_b.x).abs() < SIZE_POINT {
+                        if (position.x - point_b.x).abs() < SIZE_POINT * unit {
                             position_snap.x = point_b.x;
                         }
-                        if (position.y - point_b.y).abs() < SIZE_POINT {
+                        if (position.y - point_b.y).abs() < SIZE_POINT * unit {
                             position_snap.y = point_b.y;
                         }
                     }
@@ -340,51 +1076,72 @@ impl EditorState {
                         let point2 = Vec2::new(center.x, center.y - radius);
                         let point3 = Vec2::new(center.x + radius, center.y);
                         let point4 = Vec2::new(center.x - radius, center.y);
-                        if position.distance(center) <= STICKY_ELEMENT {
+                        if position.distance(center) <= STICKY_ELEMENT * unit {
                             position_snap = center;
                         }
-                        if position.distance(point1) <= STICKY_ELEMENT {
+                        if position.distance(point1) <= STICKY_ELEMENT * unit {
                             position_snap = point1;
                         }
-                        if position.distance(point2) <= STICKY_ELEMENT {
+                        if position.distance(point2) <= STICKY_ELEMENT * unit {
                             position_snap = point2;
                         }
-                        if position.distance(point3) <= STICKY_ELEMENT {
+                        if position.distance(point3) <= STICKY_ELEMENT * unit {
                             position_snap = point3;
                         }
-                        if position.distance(point4) <= STICKY_ELEMENT {
+                        if position.distance(point4) <= STICKY_ELEMENT * unit {
                             position_snap = point4;
                         }
 
-                        if (position.x - center.x).abs() < SIZE_POINT {
+                        if (position.x - center.x).abs() < SIZE_POINT * unit {
                             position_snap.x = center.x;
                         }
-                        if (position.y - center.y).abs() < SIZE_POINT {
+                        if (position.y - center.y).abs() < SIZE_POINT * unit {
                             position_snap.y = center.y;
                         }
-                        if (position.y - point1.y).abs() < SIZE_POINT {
+                        if (position.y - point1.y).abs() < SIZE_POINT * unit {
                             position_snap.y = point1.y;
                         }
-                        if (position.y - point2.y).abs() < SIZE_POINT {
+                        if (position.y - point2.y).abs() < SIZE_POINT * unit {
                             position_snap.y = point2.y;
                         }
-                        if (position.x - point3.x).abs() < SIZE_POINT {
+                        if (position.x - point3.x).abs() < SIZE_POINT * unit {
                             position_snap.x = point3.x;
                         }
-                        if (position.x - point4.x).abs() < SIZE_POINT {
+                        if (position.x - point4.x).abs() < SIZE_POINT * unit {
                             position_snap.x = point4.x;
                         }
 
                         let direction = (position - center).normalize();
                         let point = center + direction * radius;
 
-                        if (position.x - point.x).abs() < SIZE_POINT {
+                        if (position.x - point.x).abs() < SIZE_POINT * unit {
                             position_snap.x = point.x;
                         }
-                        if (position.y - point.y).abs() < SIZE_POINT {
+                        if (position.y - point.y).abs() < SIZE_POINT * unit {
                             position_snap.y = point.y;
                         }
                     }
+                    EditorValues::Path {
+                        start,
+                        ref segments,
+                        ..
+                    } => {
+                        // Only anchors snap, handles would pull the cursor off the curve.
+                        let anchors = segments.iter().map(|i| i.point()).chain([start]);
+                        for point in anchors {
+                            if position.distance(point) <= STICKY_ELEMENT * unit {
+                                position_snap = point;
+                            }
+                        }
+                    }
+                    EditorValues::Polyline { ref points, .. }
+                    | EditorValues::Polygon { ref points } => {
+                        for point in points {
+                            if position.distance(*point) <= STICKY_ELEMENT * unit {
+                                position_snap = *point;
+                            }
+                        }
+                    }
                     _ => {
                         // TODO...
                     }
@@ -0,0 +1,1191 @@
+use macroquad::prelude::Affine2;
+use macroquad::prelude::Color;
+use macroquad::prelude::Rect;
+use macroquad::prelude::Vec2;
+use macroquad::prelude::BLACK;
+use macroquad::prelude::WHITE;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::EditorElement;
+use super::EditorSegments;
+use super::EditorValues;
+
+pub const SVG_EXTENSION: &str = "svg";
+
+pub struct EditorSvg {}
+
+impl EditorSvg {
+    pub fn color(color: Color) -> String {
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            (color.r.clamp(0.0, 1.0) * 255.0).round() as u8,
+            (color.g.clamp(0.0, 1.0) * 255.0).round() as u8,
+            (color.b.clamp(0.0, 1.0) * 255.0).round() as u8,
+        )
+    }
+
+    fn fill(color: Option<Color>) -> String {
+        match color {
+            Some(color) => format!(
+                "fill=\"{}\" fill-opacity=\"{:.3}\"",
+                EditorSvg::color(color),
+                color.a
+            ),
+            None => "fill=\"none\"".to_string(),
+        }
+    }
+
+    fn stroke(color: Color, thickness: f32) -> String {
+        format!(
+            "stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{:.1}\"",
+            EditorSvg::color(color),
+            color.a,
+            thickness
+        )
+    }
+
+    // Fill and stroke attributes of an element, SVG strokes are centered on
+    // the outline like the editor draws them.
+    fn paint(element: &EditorElement) -> String {
+        let fill = EditorSvg::fill(element.fill);
+        match element.stroke {
+            Some(color) => format!("{} {}", fill, EditorSvg::stroke(color, element.thickness)),
+            None => fill,
+        }
+    }
+
+    fn points(points: &[Vec2]) -> String {
+        points
+            .iter()
+            .map(|i| format!("{:.1},{:.1}", i.x, i.y))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // Path data of a circular arc, full turns are split in two halves since a
+    // single SVG arc can't end where it starts.
+    fn arc(center: Vec2, radius: f32, start: f32, sweep: f32) -> String {
+        let (from, span) = EditorValues::span(start, sweep);
+        let point = |angle: f32| center + Vec2::from_angle(angle.to_radians()) * radius;
+        let halves = if span >= 360.0 { 2 } else { 1 };
+        let step = span / halves as f32;
+        let mut data = String::new();
+        for i in 0..halves {
+            let end = point(from + step * (i + 1) as f32);
+            data.push_str(&format!(
+                " A{:.1},{:.1} 0 {} 1 {:.1},{:.1}",
+                radius,
+                radius,
+                u8::from(step > 180.0),
+                end.x,
+                end.y
+            ));
+        }
+        let start = point(from);
+        format!("{:.1},{:.1}{}", start.x, start.y, data)
+    }
+
+    pub fn element(element: &EditorElement) -> String {
+        let paint = EditorSvg::paint(element);
+        match element.value {
+            EditorValues::Line { point_a, point_b } => format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" {}/>",
+                point_a.x, point_a.y, point_b.x, point_b.y, paint,
+            ),
+            EditorValues::Circle { center, radius } => format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" {}/>",
+                center.x, center.y, radius, paint,
+            ),
+            EditorValues::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            } => format!(
+                "<path d=\"M{}\" {}/>",
+                EditorSvg::arc(center, radius, start, sweep),
+                paint,
+            ),
+            EditorValues::Pie {
+                center,
+                radius,
+                start,
+                sweep,
+            } => format!(
+                "<path d=\"M{:.1},{:.1} L{} Z\" {}/>",
+                center.x,
+                center.y,
+                EditorSvg::arc(center, radius, start, sweep),
+                paint,
+            ),
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation,
+            } => format!(
+                "<ellipse cx=\"{:.1}\" cy=\"{:.1}\" rx=\"{:.1}\" ry=\"{:.1}\" transform=\"rotate({:.1} {:.1} {:.1})\" {}/>",
+                center.x,
+                center.y,
+                width,
+                height,
+                rotation,
+                center.x,
+                center.y,
+                paint,
+            ),
+            EditorValues::Rectangle {
+                point,
+                width,
+                height,
+                rotation,
+            } => format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" transform=\"rotate({:.1} {:.1} {:.1})\" {}/>",
+                point.x,
+                point.y,
+                width,
+                height,
+                rotation.to_degrees(),
+                point.x,
+                point.y,
+                paint,
+            ),
+            EditorValues::Triangle { .. }
+            | EditorValues::Hexagon { .. }
+            | EditorValues::Poly { .. } => format!(
+                "<polygon points=\"{}\" {}/>",
+                EditorSvg::points(&element.value.corners()),
+                paint,
+            ),
+            EditorValues::Polyline { ref points } => format!(
+                "<polyline points=\"{}\" {}/>",
+                EditorSvg::points(points),
+                paint,
+            ),
+            EditorValues::Polygon { ref points } => format!(
+                "<polygon points=\"{}\" {}/>",
+                EditorSvg::points(points),
+                paint,
+            ),
+            EditorValues::Path {
+                start,
+                ref segments,
+                closed,
+            } => format!(
+                "<path d=\"{}\" {}/>",
+                EditorSegments::svg(start, segments, closed),
+                paint,
+            ),
+        }
+    }
+
+    pub fn document(stack: &[EditorElement], frame: Rect) -> String {
+        let mut content = String::new();
+        content.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{:.1} {:.1} {:.1} {:.1}\" width=\"{:.1}\" height=\"{:.1}\">\n",
+            frame.x, frame.y, frame.w, frame.h, frame.w, frame.h
+        ));
+        for i in stack {
+            content.push_str(&format!("  {}\n", EditorSvg::element(i)));
+        }
+        content.push_str("</svg>\n");
+        content
+    }
+
+    pub fn write(path: &Path, stack: &[EditorElement], frame: Rect) -> Result<(), Box<dyn Error>> {
+        fs::write(path, EditorSvg::document(stack, frame))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EditorSvgImport {
+    pub elements: Vec<EditorElement>,
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct EditorSvgStyle {
+    transform: Affine2,
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f32,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    // Why shapes under this style can't be imported.
+    unsupported: Option<String>,
+}
+
+// Reads path data one command, number or arc flag at a time.
+struct EditorSvgPath<'a> {
+    data: &'a [u8],
+    index: usize,
+}
+
+impl Default for EditorSvgStyle {
+    fn default() -> Self {
+        Self {
+            transform: Affine2::IDENTITY,
+            fill: Some(BLACK),
+            stroke: None,
+            stroke_width: 1.0,
+            opacity: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            unsupported: None,
+        }
+    }
+}
+
+impl EditorSvgStyle {
+    fn inherit(&self, attributes: &HashMap<String, String>) -> Self {
+        let mut style = self.clone();
+        let mut properties: Vec<(String, String)> = attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        // Declarations in `style` take precedence over presentation attributes.
+        if let Some(value) = attributes.get("style") {
+            for declaration in value.split(';') {
+                if let Some((k, v)) = declaration.split_once(':') {
+                    properties.push((k.trim().to_string(), v.trim().to_string()));
+                }
+            }
+        }
+        for (key, value) in properties {
+            match key.as_str() {
+                "fill" => style.fill = EditorSvg::parse_color(&value),
+                "stroke" => style.stroke = EditorSvg::parse_color(&value),
+                "stroke-width" => {
+                    if let Some(i) = EditorSvg::parse_numbers(&value).first() {
+                        style.stroke_width = *i;
+                    }
+                }
+                "opacity" => {
+                    if let Some(i) = EditorSvg::parse_numbers(&value).first() {
+                        style.opacity = self.opacity * i.clamp(0.0, 1.0);
+                    }
+                }
+                "fill-opacity" => {
+                    if let Some(i) = EditorSvg::parse_numbers(&value).first() {
+                        style.fill_opacity = i.clamp(0.0, 1.0);
+                    }
+                }
+                "stroke-opacity" => {
+                    if let Some(i) = EditorSvg::parse_numbers(&value).first() {
+                        style.stroke_opacity = i.clamp(0.0, 1.0);
+                    }
+                }
+                "transform" => match EditorSvg::parse_transform(&value) {
+                    Ok(transform) => style.transform = self.transform * transform,
+                    Err(e) => style.unsupported = Some(e),
+                },
+                _ => {}
+            }
+        }
+        style
+    }
+
+    fn fill(&self) -> Option<Color> {
+        self.fill
+            .map(|i| i.with_alpha(i.a * self.opacity * self.fill_opacity))
+    }
+
+    fn stroke(&self) -> Option<Color> {
+        self.stroke
+            .map(|i| i.with_alpha(i.a * self.opacity * self.stroke_opacity))
+    }
+
+    // Stroke width scaled by the average scale of the transform.
+    fn thickness(&self) -> f32 {
+        self.stroke_width * self.transform.matrix2.determinant().abs().sqrt()
+    }
+}
+
+impl EditorSvg {
+    pub fn parse_numbers(text: &str) -> Vec<f32> {
+        let mut numbers = Vec::new();
+        let mut number = String::new();
+        let mut previous = ' ';
+        for char in text.chars() {
+            let sign = (char == '-' || char == '+') && previous != 'e' && previous != 'E';
+            let dot = char == '.' && number.contains('.') && !number.contains(['e', 'E']);
+            if sign || dot || !(char.is_ascii_digit() || "+-.eE".contains(char)) {
+                if let Ok(i) = number.parse::<f32>() {
+                    numbers.push(i);
+                }
+                number.clear();
+            }
+            if char.is_ascii_digit() || "+-.eE".contains(char) {
+                number.push(char);
+            }
+            previous = char;
+        }
+        if let Ok(i) = number.parse::<f32>() {
+            numbers.push(i);
+        }
+        numbers
+    }
+
+    pub fn parse_color(text: &str) -> Option<Color> {
+        let text = text.trim().to_lowercase();
+        if let Some(hex) = text.strip_prefix('#') {
+            let hex: String = match hex.len() {
+                3 | 4 => hex.chars().flat_map(|i| [i, i]).collect(),
+                _ => hex.to_string(),
+            };
+            let value = u32::from_str_radix(hex.get(..6)?, 16).ok()?;
+            // Eight digits carry the alpha last.
+            let alpha = match hex.get(6..8) {
+                Some(alpha) => u8::from_str_radix(alpha, 16).ok()?,
+                None => 255,
+            };
+            return Some(Color::from_rgba(
+                (value >> 16) as u8,
+                (value >> 8) as u8,
+                value as u8,
+                alpha,
+            ));
+        }
+        if let Some(rgb) = text.strip_prefix("rgb") {
+            let values = EditorSvg::parse_numbers(rgb);
+            let percent = rgb.contains('%');
+            let channel = |i: usize| {
+                let value = values.get(i).copied().unwrap_or(0.0);
+                if percent {
+                    value / 100.0
+                } else {
+                    value / 255.0
+                }
+            };
+            let alpha = values.get(3).copied().unwrap_or(1.0);
+            return Some(Color::new(channel(0), channel(1), channel(2), alpha));
+        }
+        match text.as_str() {
+            "none" | "transparent" => None,
+            "black" => Some(BLACK),
+            "white" => Some(WHITE),
+            "red" => Some(Color::from_rgba(255, 0, 0, 255)),
+            "green" => Some(Color::from_rgba(0, 128, 0, 255)),
+            "lime" => Some(Color::from_rgba(0, 255, 0, 255)),
+            "blue" => Some(Color::from_rgba(0, 0, 255, 255)),
+            "yellow" => Some(Color::from_rgba(255, 255, 0, 255)),
+            "orange" => Some(Color::from_rgba(255, 165, 0, 255)),
+            "purple" => Some(Color::from_rgba(128, 0, 128, 255)),
+            "pink" => Some(Color::from_rgba(255, 192, 203, 255)),
+            "gray" | "grey" => Some(Color::from_rgba(128, 128, 128, 255)),
+            "silver" => Some(Color::from_rgba(192, 192, 192, 255)),
+            "cyan" | "aqua" => Some(Color::from_rgba(0, 255, 255, 255)),
+            "magenta" | "fuchsia" => Some(Color::from_rgba(255, 0, 255, 255)),
+            _ => None,
+        }
+    }
+
+    pub fn parse_transform(text: &str) -> Result<Affine2, String> {
+        let mut transform = Affine2::IDENTITY;
+        for part in text.split(')') {
+            let Some((name, values)) = part.split_once('(') else {
+                continue;
+            };
+            let v = EditorSvg::parse_numbers(values);
+            let value = |i: usize, default: f32| v.get(i).copied().unwrap_or(default);
+            let name = name.trim().trim_start_matches(',').trim();
+            let next = match name {
+                "translate" => Affine2::from_translation(Vec2::new(value(0, 0.0), value(1, 0.0))),
+                "scale" => Affine2::from_scale(Vec2::new(value(0, 1.0), value(1, value(0, 1.0)))),
+                "rotate" => {
+                    let center = Vec2::new(value(1, 0.0), value(2, 0.0));
+                    Affine2::from_translation(center)
+                        * Affine2::from_angle(value(0, 0.0).to_radians())
+                        * Affine2::from_translation(-center)
+                }
+                "skewX" => Affine2::from_cols_array(&[
+                    1.0,
+                    0.0,
+                    value(0, 0.0).to_radians().tan(),
+                    1.0,
+                    0.0,
+                    0.0,
+                ]),
+                "skewY" => Affine2::from_cols_array(&[
+                    1.0,
+                    value(0, 0.0).to_radians().tan(),
+                    0.0,
+                    1.0,
+                    0.0,
+                    0.0,
+                ]),
+                "matrix" if v.len() == 6 => Affine2::from_cols_array(&[
+                    value(0, 1.0),
+                    value(1, 0.0),
+                    value(2, 0.0),
+                    value(3, 1.0),
+                    value(4, 0.0),
+                    value(5, 0.0),
+                ]),
+                _ => return Err(format!("unsupported transform {}({})", name, values.trim())),
+            };
+            transform *= next;
+        }
+        Ok(transform)
+    }
+
+    // Paths of the `d` attribute, one for each subpath. Arcs become cubic
+    // curves.
+    pub fn parse_path(text: &str) -> Result<Vec<EditorValues>, String> {
+        let mut data = EditorSvgPath {
+            data: text.as_bytes(),
+            index: 0,
+        };
+        let mut paths = Vec::new();
+        let mut segments = Vec::new();
+        let mut start = Vec2::ZERO;
+        let mut current = Vec2::ZERO;
+        // Last command with the control point smooth curves mirror.
+        let mut previous: Option<(u8, Vec2)> = None;
+        let mut command = 0;
+        let mut moved = false;
+
+        while !data.done() {
+            command = match data.command() {
+                Some(i) => i,
+                None => match command {
+                    b'M' => b'L',
+                    b'm' => b'l',
+                    0 | b'Z' | b'z' => return Err("path data is missing a command".to_string()),
+                    i => i,
+                },
+            };
+            if !moved && !matches!(command, b'M' | b'm') {
+                return Err("path data does not start with a move".to_string());
+            }
+            moved = true;
+            let base = if command.is_ascii_lowercase() {
+                current
+            } else {
+                Vec2::ZERO
+            };
+            let kind = command.to_ascii_uppercase();
+            let mirror = |kinds: [u8; 2]| match previous {
+                Some((kind, control)) if kinds.contains(&kind) => current * 2.0 - control,
+                _ => current,
+            };
+            let mut control = None;
+            match kind {
+                b'M' => {
+                    if !segments.is_empty() {
+                        let segments = std::mem::take(&mut segments);
+                        paths.push(EditorValues::Path {
+                            start,
+                            segments,
+                            closed: false,
+                        });
+                    }
+                    current = data.point(base)?;
+                    start = current;
+                }
+                b'L' => {
+                    current = data.point(base)?;
+                    segments.push(EditorSegments::Line { point: current });
+                }
+                b'H' => {
+                    current.x = base.x + data.number()?;
+                    segments.push(EditorSegments::Line { point: current });
+                }
+                b'V' => {
+                    current.y = base.y + data.number()?;
+                    segments.push(EditorSegments::Line { point: current });
+                }
+                b'C' | b'S' => {
+                    let control_a = match kind {
+                        b'C' => data.point(base)?,
+                        _ => mirror([b'C', b'S']),
+                    };
+                    let control_b = data.point(base)?;
+                    current = data.point(base)?;
+                    control = Some(control_b);
+                    segments.push(EditorSegments::Cubic {
+                        control_a,
+                        control_b,
+                        point: current,
+                    });
+                }
+                b'Q' | b'T' => {
+                    let point = match kind {
+                        b'Q' => data.point(base)?,
+                        _ => mirror([b'Q', b'T']),
+                    };
+                    current = data.point(base)?;
+                    control = Some(point);
+                    segments.push(EditorSegments::Quadratic {
+                        control: point,
+                        point: current,
+                    });
+                }
+                b'A' => {
+                    let radii = Vec2::new(data.number()?, data.number()?);
+                    let rotation = data.number()?;
+                    let large = data.flag()?;
+                    let sweep = data.flag()?;
+                    let point = data.point(base)?;
+                    let arc = EditorSegments::arc(current, point, radii, rotation, large, sweep);
+                    segments.extend(arc);
+                    current = point;
+                }
+                b'Z' => {
+                    if current != start {
+                        segments.push(EditorSegments::Line { point: start });
+                    }
+                    if !segments.is_empty() {
+                        let segments = std::mem::take(&mut segments);
+                        paths.push(EditorValues::Path {
+                            start,
+                            segments,
+                            closed: true,
+                        });
+                    }
+                    current = start;
+                }
+                _ => return Err(format!("path command {} is not supported", command as char)),
+            }
+            previous = control.map(|i| (kind, i));
+        }
+        if !segments.is_empty() {
+            paths.push(EditorValues::Path {
+                start,
+                segments,
+                closed: false,
+            });
+        }
+        Ok(paths)
+    }
+
+    fn parse_attributes(tag: &str) -> HashMap<String, String> {
+        let mut attributes = HashMap::new();
+        let mut rest = tag;
+        while let Some(index) = rest.find('=') {
+            let key = rest[..index].split_whitespace().last().unwrap_or("");
+            let value = rest[index + 1..].trim_start();
+            let Some(quote) = value.chars().next().filter(|i| *i == '"' || *i == '\'') else {
+                break;
+            };
+            let Some(end) = value[1..].find(quote) else {
+                break;
+            };
+            attributes.insert(key.to_string(), value[1..end + 1].to_string());
+            rest = &value[end + 2..];
+        }
+        attributes
+    }
+
+    // Ellipse axes after the transform, `None` when the transform skews them.
+    fn axes(transform: Affine2, x: Vec2, y: Vec2) -> Option<(Vec2, Vec2)> {
+        let x = transform.matrix2 * x;
+        let y = transform.matrix2 * y;
+        if x.dot(y).abs() > 0.001 * x.length() * y.length() {
+            return None;
+        }
+        Some((x, y))
+    }
+
+    // Circle or ellipse with radii `rx` and `ry` after the transform, skews
+    // turn and stretch its axes.
+    fn ellipse(transform: Affine2, center: Vec2, rx: f32, ry: f32) -> EditorValues {
+        let x = transform.matrix2 * Vec2::new(rx, 0.0);
+        let y = transform.matrix2 * Vec2::new(0.0, ry);
+        // Axes from the eigenvectors of M * M^T, where M has the columns x and y.
+        let a = x.x * x.x + y.x * y.x;
+        let b = x.x * x.y + y.x * y.y;
+        let c = x.y * x.y + y.y * y.y;
+        let mean = (a + c) / 2.0;
+        let spread = (((a - c) / 2.0).powi(2) + b * b).sqrt();
+        let width = (mean + spread).max(0.0).sqrt();
+        let height = (mean - spread).max(0.0).sqrt();
+        let center = transform.transform_point2(center);
+        if (width - height).abs() < 0.01 {
+            EditorValues::Circle {
+                center,
+                radius: width,
+            }
+        } else {
+            EditorValues::Ellipse {
+                center,
+                width,
+                height,
+                rotation: (0.5 * (2.0 * b).atan2(a - c)).to_degrees(),
+            }
+        }
+    }
+
+    // Paths of `data` after the transform.
+    fn path(data: &str, transform: Affine2) -> Result<Vec<EditorValues>, String> {
+        let mut paths = EditorSvg::parse_path(data)?;
+        for i in paths.iter_mut() {
+            if let EditorValues::Path {
+                start, segments, ..
+            } = i
+            {
+                *start = transform.transform_point2(*start);
+                for segment in segments.iter_mut() {
+                    segment.map(|p| transform.transform_point2(p));
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    fn convert(
+        name: &str,
+        attributes: &HashMap<String, String>,
+        style: &EditorSvgStyle,
+    ) -> Result<Vec<EditorElement>, String> {
+        if let Some(e) = &style.unsupported {
+            return Err(format!("{} has an {}", name, e));
+        }
+        let number = |key: &str| {
+            attributes
+                .get(key)
+                .and_then(|i| EditorSvg::parse_numbers(i).first().copied())
+                .unwrap_or(0.0)
+        };
+        let transform = style.transform;
+
+        let values = match name {
+            "line" => {
+                let point_a = transform.transform_point2(Vec2::new(number("x1"), number("y1")));
+                let point_b = transform.transform_point2(Vec2::new(number("x2"), number("y2")));
+                vec![EditorValues::Line { point_a, point_b }]
+            }
+            "circle" => {
+                let center = Vec2::new(number("cx"), number("cy"));
+                vec![EditorSvg::ellipse(
+                    transform,
+                    center,
+                    number("r"),
+                    number("r"),
+                )]
+            }
+            "ellipse" => {
+                let center = Vec2::new(number("cx"), number("cy"));
+                vec![EditorSvg::ellipse(
+                    transform,
+                    center,
+                    number("rx"),
+                    number("ry"),
+                )]
+            }
+            "rect" => {
+                let origin = Vec2::new(number("x"), number("y"));
+                let size = Vec2::new(number("width"), number("height"));
+                // A missing corner radius takes the other one.
+                let radius = |key: &str, other: &str| {
+                    let key = if attributes.contains_key(key) {
+                        key
+                    } else {
+                        other
+                    };
+                    number(key)
+                };
+                let rx = radius("rx", "ry").clamp(0.0, size.x / 2.0);
+                let ry = radius("ry", "rx").clamp(0.0, size.y / 2.0);
+                let axes =
+                    EditorSvg::axes(transform, Vec2::new(size.x, 0.0), Vec2::new(0.0, size.y));
+                if rx > 0.0 && ry > 0.0 {
+                    // Rounded corners are drawn as a path of lines and arcs.
+                    let (x, y) = (origin.x, origin.y);
+                    let (right, bottom) = (x + size.x, y + size.y);
+                    let arc = |x: f32, y: f32| format!("A{} {} 0 0 1 {} {}", rx, ry, x, y);
+                    let data = format!(
+                        "M{} {} H{} {} V{} {} H{} {} V{} {} Z",
+                        x + rx,
+                        y,
+                        right - rx,
+                        arc(right, y + ry),
+                        bottom - ry,
+                        arc(right - rx, bottom),
+                        x + rx,
+                        arc(x, bottom - ry),
+                        y + ry,
+                        arc(x + rx, y),
+                    );
+                    EditorSvg::path(&data, transform)?
+                } else if let Some((x, y)) = axes {
+                    // Mirrored rectangles start from the opposite corner to keep the
+                    // height axis turned clockwise from the width axis.
+                    let point = if x.perp_dot(y) < 0.0 {
+                        transform.transform_point2(origin + Vec2::new(0.0, size.y))
+                    } else {
+                        transform.transform_point2(origin)
+                    };
+                    vec![EditorValues::Rectangle {
+                        point,
+                        width: x.length(),
+                        height: y.length(),
+                        rotation: x.y.atan2(x.x),
+                    }]
+                } else {
+                    // Skews turn rectangles into parallelograms.
+                    let points = [
+                        origin,
+                        origin + Vec2::new(size.x, 0.0),
+                        origin + size,
+                        origin + Vec2::new(0.0, size.y),
+                    ];
+                    let points = points.map(|i| transform.transform_point2(i)).to_vec();
+                    vec![EditorValues::Polygon { points }]
+                }
+            }
+            "polygon" | "polyline" => {
+                let numbers = attributes
+                    .get("points")
+                    .map(|i| EditorSvg::parse_numbers(i))
+                    .unwrap_or_default();
+                let points: Vec<Vec2> = numbers
+                    .chunks_exact(2)
+                    .map(|i| transform.transform_point2(Vec2::new(i[0], i[1])))
+                    .collect();
+                if points.len() < 2 {
+                    return Err(format!("{} has less than two points", name));
+                }
+                if name == "polygon" && points.len() > 2 {
+                    vec![EditorValues::Polygon { points }]
+                } else {
+                    vec![EditorValues::Polyline { points }]
+                }
+            }
+            "path" => {
+                let data = attributes.get("d").map(String::as_str).unwrap_or("");
+                let mut paths = EditorSvg::path(data, transform)?;
+                // Fills close open subpaths, so paths with only a fill stay closed.
+                if style.stroke().is_none() {
+                    for i in paths.iter_mut() {
+                        if let EditorValues::Path { closed, .. } = i {
+                            *closed = true;
+                        }
+                    }
+                }
+                paths
+            }
+            _ => return Err(format!("{} is not supported", name)),
+        };
+        if values.is_empty() {
+            return Err(format!("{} has no shape", name));
+        }
+
+        // Open shapes drop their fill, the editor only strokes them.
+        let mut elements = Vec::new();
+        for value in values {
+            let element =
+                EditorElement::new(value, style.fill(), style.stroke(), style.thickness());
+            if element.fill.is_none() && element.stroke.is_none() {
+                return Err(format!("{} has neither fill nor stroke", name));
+            }
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+
+    pub fn import(content: &str) -> EditorSvgImport {
+        let mut import = EditorSvgImport::default();
+        let mut styles = vec![EditorSvgStyle::default()];
+        let mut hidden: usize = 0;
+        let mut rest = content;
+
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            if rest.starts_with("<!--") {
+                let end = rest.find("-->").map(|i| i + 3).unwrap_or(rest.len());
+                rest = &rest[end..];
+                continue;
+            }
+            let Some(end) = rest.find('>') else {
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                match name.trim() {
+                    "g" | "svg" | "a" if styles.len() > 1 => {
+                        styles.pop();
+                    }
+                    "defs" | "symbol" | "clipPath" | "mask" | "pattern" => {
+                        hidden = hidden.saturating_sub(1);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let closed = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name = tag.split_whitespace().next().unwrap_or("");
+            let attributes = EditorSvg::parse_attributes(&tag[name.len()..]);
+
+            match name {
+                "g" | "svg" | "a" => {
+                    // The viewBox, width and height of svg elements are not
+                    // applied, shapes keep user units.
+                    if !closed {
+                        styles.push(styles[styles.len() - 1].inherit(&attributes));
+                    }
+                }
+                "defs" | "symbol" | "clipPath" | "mask" | "pattern" => {
+                    if !closed {
+                        hidden += 1;
+                    }
+                }
+                "title" | "desc" | "metadata" | "style" => {}
+                _ if hidden > 0 => {}
+                _ => {
+                    let style = styles[styles.len() - 1].inherit(&attributes);
+                    match EditorSvg::convert(name, &attributes, &style) {
+                        Ok(elements) => import.elements.extend(elements),
+                        Err(e) => import.skipped.push(e),
+                    }
+                }
+            }
+        }
+        import
+    }
+
+    pub fn read(path: &Path) -> Result<EditorSvgImport, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(EditorSvg::import(&content))
+    }
+}
+
+impl EditorSvgPath<'_> {
+    fn skip(&mut self) {
+        while self
+            .data
+            .get(self.index)
+            .is_some_and(|i| i.is_ascii_whitespace() || *i == b',')
+        {
+            self.index += 1;
+        }
+    }
+
+    fn done(&mut self) -> bool {
+        self.skip();
+        self.index >= self.data.len()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip();
+        let char = *self.data.get(self.index)?;
+        if !char.is_ascii_alphabetic() || char == b'e' || char == b'E' {
+            return None;
+        }
+        self.index += 1;
+        Some(char)
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip();
+        let digits = |data: &[u8], mut i: usize| {
+            while data.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            i
+        };
+        let start = self.index;
+        let mut end = start;
+        if matches!(self.data.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        let whole = end;
+        end = digits(self.data, end);
+        if self.data.get(end) == Some(&b'.') {
+            end = digits(self.data, end + 1);
+        }
+        // The exponent only counts with digits after it.
+        if matches!(self.data.get(end), Some(b'e' | b'E')) {
+            let mut exponent = end + 1;
+            if matches!(self.data.get(exponent), Some(b'+' | b'-')) {
+                exponent += 1;
+            }
+            if self.data.get(exponent).is_some_and(u8::is_ascii_digit) {
+                end = digits(self.data, exponent);
+            }
+        }
+        let text = String::from_utf8_lossy(&self.data[start..end]);
+        match text[whole - start..].trim_start_matches('.').is_empty() {
+            true => Err("path data is missing a number".to_string()),
+            false => {
+                self.index = end;
+                text.parse::<f32>()
+                    .map_err(|e| format!("path data has {}: {}", text, e))
+            }
+        }
+    }
+
+    fn point(&mut self, base: Vec2) -> Result<Vec2, String> {
+        Ok(base + Vec2::new(self.number()?, self.number()?))
+    }
+
+    // Arc flags are single digits that need no separator.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip();
+        let flag = match self.data.get(self.index) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err("path data is missing an arc flag".to_string()),
+        };
+        self.index += 1;
+        Ok(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::Color;
+    use macroquad::prelude::Vec2;
+
+    use super::EditorSvg;
+    use crate::editor::EditorSegments;
+    use crate::editor::EditorValues;
+
+    fn close(a: Vec2, b: Vec2) -> bool {
+        a.distance(b) < 0.001
+    }
+
+    fn segments(data: &str) -> Vec<EditorSegments> {
+        match EditorSvg::parse_path(data).unwrap().as_slice() {
+            [EditorValues::Path { segments, .. }] => segments.clone(),
+            paths => panic!("expected one path, got {:?}", paths),
+        }
+    }
+
+    #[test]
+    fn parses_absolute_and_relative_path_commands() {
+        let line = |x, y| EditorSegments::Line {
+            point: Vec2::new(x, y),
+        };
+        let paths = EditorSvg::parse_path("M10,10 h10 v10 H10 z m5 5 L30 15 l0-10").unwrap();
+        assert_eq!(
+            paths,
+            [
+                EditorValues::Path {
+                    start: Vec2::new(10.0, 10.0),
+                    segments: vec![
+                        line(20.0, 10.0),
+                        line(20.0, 20.0),
+                        line(10.0, 20.0),
+                        line(10.0, 10.0)
+                    ],
+                    closed: true,
+                },
+                EditorValues::Path {
+                    start: Vec2::new(15.0, 15.0),
+                    segments: vec![line(30.0, 15.0), line(30.0, 5.0)],
+                    closed: false,
+                },
+            ]
+        );
+        // Pairs after a move are lines.
+        assert_eq!(segments("m1 1 2 0 0 2"), [line(3.0, 1.0), line(3.0, 3.0)]);
+        assert!(EditorSvg::parse_path("L10 10").is_err());
+        assert!(EditorSvg::parse_path("M0 0 L10").is_err());
+    }
+
+    #[test]
+    fn smooth_curves_mirror_the_previous_control() {
+        assert_eq!(
+            segments("M0 0 C0 10 10 10 10 0 s10 -10 10 0"),
+            [
+                EditorSegments::Cubic {
+                    control_a: Vec2::new(0.0, 10.0),
+                    control_b: Vec2::new(10.0, 10.0),
+                    point: Vec2::new(10.0, 0.0),
+                },
+                EditorSegments::Cubic {
+                    control_a: Vec2::new(10.0, -10.0),
+                    control_b: Vec2::new(20.0, -10.0),
+                    point: Vec2::new(20.0, 0.0),
+                },
+            ]
+        );
+        assert_eq!(
+            segments("M0 0 Q5 10 10 0 T20 0 L30 0 T40 0"),
+            [
+                EditorSegments::Quadratic {
+                    control: Vec2::new(5.0, 10.0),
+                    point: Vec2::new(10.0, 0.0),
+                },
+                EditorSegments::Quadratic {
+                    control: Vec2::new(15.0, -10.0),
+                    point: Vec2::new(20.0, 0.0),
+                },
+                EditorSegments::Line {
+                    point: Vec2::new(30.0, 0.0),
+                },
+                // Without a curve before it the control is the current point.
+                EditorSegments::Quadratic {
+                    control: Vec2::new(30.0, 0.0),
+                    point: Vec2::new(40.0, 0.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn arcs_become_quarter_turn_cubics() {
+        let arc = segments("M0 0 A10 10 0 0 1 20 0");
+        assert_eq!(arc.len(), 2);
+        assert!(close(arc[0].point(), Vec2::new(10.0, -10.0)));
+        assert_eq!(arc[1].point(), Vec2::new(20.0, 0.0));
+        let points = EditorSegments::flatten(Vec2::ZERO, &arc);
+        for i in points {
+            assert!((i.distance(Vec2::new(10.0, 0.0)) - 10.0).abs() < 0.05);
+        }
+
+        // Flags need no separators and the other sweep goes below.
+        let arc = segments("m0 0 a10 10 0 0020 0");
+        assert!(close(arc[0].point(), Vec2::new(10.0, 10.0)));
+        // Radii too small to reach the end are scaled up.
+        let arc = segments("M0 0 A1 1 0 0 1 20 0");
+        assert!(close(arc[0].point(), Vec2::new(10.0, -10.0)));
+    }
+
+    #[test]
+    fn nested_groups_combine_transforms() {
+        let import = EditorSvg::import(
+            "<svg><g transform=\"translate(10 0)\"><g transform=\"scale(2)\">\
+             <circle cx=\"1\" cy=\"1\" r=\"1\"/></g>\
+             <rect width=\"10\" height=\"10\" transform=\"skewX(45)\"/></g>\
+             <ellipse rx=\"2\" ry=\"1\" transform=\"rotate(90)\"/></svg>",
+        );
+        assert!(import.skipped.is_empty());
+        assert_eq!(
+            import.elements[0].value,
+            EditorValues::Circle {
+                center: Vec2::new(12.0, 2.0),
+                radius: 2.0,
+            }
+        );
+        let EditorValues::Polygon { ref points } = import.elements[1].value else {
+            panic!("skewed rect is not a polygon");
+        };
+        let expected = [(10.0, 0.0), (20.0, 0.0), (30.0, 10.0), (20.0, 10.0)];
+        for (a, (x, y)) in points.iter().zip(expected) {
+            assert!(close(*a, Vec2::new(x, y)));
+        }
+        let EditorValues::Ellipse {
+            width,
+            height,
+            rotation,
+            ..
+        } = import.elements[2].value
+        else {
+            panic!("rotated ellipse is not an ellipse");
+        };
+        assert!((width - 2.0).abs() < 0.001 && (height - 1.0).abs() < 0.001);
+        // Half turns give the same ellipse.
+        assert!((rotation.rem_euclid(180.0) - 90.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn parses_colors_and_opacity() {
+        let red = Color::from_rgba(255, 0, 0, 255);
+        assert_eq!(EditorSvg::parse_color("#f00"), Some(red));
+        assert_eq!(EditorSvg::parse_color(" #FF0000 "), Some(red));
+        assert_eq!(EditorSvg::parse_color("rgb(255, 0, 0)"), Some(red));
+        assert_eq!(EditorSvg::parse_color("rgb(100%,0%,0%)"), Some(red));
+        assert_eq!(EditorSvg::parse_color("red"), Some(red));
+        assert_eq!(
+            EditorSvg::parse_color("#ff000080"),
+            Some(Color::from_rgba(255, 0, 0, 128))
+        );
+        assert_eq!(EditorSvg::parse_color("none"), None);
+        assert_eq!(EditorSvg::parse_color("#12"), None);
+
+        let import = EditorSvg::import(
+            "<g opacity=\"0.5\"><rect width=\"1\" height=\"1\" \
+             style=\"fill: blue; fill-opacity: 0.5\" stroke=\"red\"/></g>",
+        );
+        let element = &import.elements[0];
+        assert_eq!(element.fill.map(|i| i.a), Some(0.25));
+        assert_eq!(element.stroke.map(|i| i.a), Some(0.5));
+    }
+
+    #[test]
+    fn root_styles_reach_the_shapes() {
+        let import = EditorSvg::import(
+            "<svg viewBox=\"0 0 10 10\" width=\"100\" fill=\"none\" stroke=\"#ff0000\" \
+             transform=\"translate(1 0)\"><line x2=\"10\"/><circle r=\"1\"/>\
+             <svg transform=\"scale(2)\"><circle r=\"1\"/></svg></svg>",
+        );
+        assert!(import.skipped.is_empty());
+        let red = Some(Color::from_rgba(255, 0, 0, 255));
+        for element in import.elements.iter() {
+            assert_eq!((element.fill, element.stroke), (None, red));
+        }
+        assert_eq!(
+            import.elements[1].value,
+            EditorValues::Circle {
+                center: Vec2::new(1.0, 0.0),
+                radius: 1.0,
+            }
+        );
+        assert_eq!(
+            import.elements[2].value,
+            EditorValues::Circle {
+                center: Vec2::new(1.0, 0.0),
+                radius: 2.0,
+            }
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_tags_keep_defs_hidden() {
+        let import = EditorSvg::import("</defs><defs><circle r=\"1\"/></defs><circle r=\"2\"/>");
+        assert_eq!(import.elements.len(), 1);
+    }
+
+    #[test]
+    fn reports_what_it_skips() {
+        let import = EditorSvg::import(
+            "<svg><!-- <circle r=\"1\"/> --><defs><circle r=\"1\"/></defs>\
+             <text>SHIP</text>\
+             <line x2=\"10\"/>\
+             <g transform=\"perspective(2)\"><circle r=\"1\"/></g>\
+             <path d=\"M0 0 X10\"/>\
+             <rect width=\"10\" height=\"4\" rx=\"1\"/></svg>",
+        );
+        assert_eq!(
+            import.skipped,
+            [
+                "text is not supported",
+                "line has neither fill nor stroke",
+                "circle has an unsupported transform perspective(2)",
+                "path command X is not supported",
+            ]
+        );
+        // Rounded rectangles keep their corners as arcs.
+        assert_eq!(import.elements.len(), 1);
+        let EditorValues::Path {
+            ref segments,
+            closed,
+            ..
+        } = import.elements[0].value
+        else {
+            panic!("rounded rect is not a path");
+        };
+        assert!(closed);
+        let cubics = segments
+            .iter()
+            .filter(|i| matches!(i, EditorSegments::Cubic { .. }))
+            .count();
+        assert_eq!(cubics, 4);
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 mod editor;
 pub use editor::*;
 
@@ -21,3 +22,51 @@ pub use editor_state::*;
 
 mod editor_config;
 pub use editor_config::*;
+
+mod editor_document;
+pub use editor_document::*;
+
+pub mod editor_prompt;
+pub use editor_prompt::*;
+
+mod editor_export;
+pub use editor_export::*;
+
+mod editor_svg;
+pub use editor_svg::*;
+
+mod editor_raster;
+pub use editor_raster::*;
+
+pub mod editor_marquee;
+pub use editor_marquee::*;
+
+pub mod editor_handles;
+pub use editor_handles::*;
+
+mod editor_clipboard;
+pub use editor_clipboard::*;
+
+mod editor_path;
+pub use editor_path::*;
+
+mod editor_camera;
+pub use editor_camera::*;
+
+mod editor_artboard;
+pub use editor_artboard::*;
+
+mod editor_picker;
+pub use editor_picker::*;
+
+mod editor_palette;
+pub use editor_palette::*;
+
+mod editor_layer;
+pub use editor_layer::*;
+
+mod editor_order;
+pub use editor_order::*;
+
+mod editor_snapshot;
+pub use editor_snapshot::*;
@@ -1,8 +1,13 @@
 mod editor;
 use editor::*;
+use std::env;
+use std::path::PathBuf;
 
 #[macroquad::main(default)]
 async fn main() {
     let mut editor = Editor::new();
+    if let Some(path) = env::args().nth(1) {
+        editor.open(PathBuf::from(path));
+    }
     editor.run().await;
 }